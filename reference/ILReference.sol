@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.20;
+
+/// @title Reference implementation of the IL Insurance valuation
+/// @notice The Solidity specification of `calculate_il` / `calculate_payout` (basket benchmark, USD
+/// prices). `tests/differential.rs` mirrors it line by line and checks the Stylus contract against it.
+/// Amounts, prices and fractions are scaled by 1e18; policy parameters are in basis points.
+library ILReference {
+    uint256 internal constant SCALE = 1e18;
+    uint256 internal constant BPS_DENOMINATOR = 10_000;
+
+    /// @notice Impermanent loss of an LP position against holding the original deposit
+    function calculateIL(
+        uint256 reserveA,
+        uint256 reserveB,
+        uint256 lpTotalSupply,
+        uint256 priceA,
+        uint256 priceB,
+        uint256 lpAmount,
+        uint256 originalA,
+        uint256 originalB
+    ) internal pure returns (uint256) {
+        uint256 lp = lpValue(reserveA, reserveB, lpTotalSupply, priceA, priceB, lpAmount);
+        uint256 holding = holdingValue(originalA, originalB, priceA, priceB);
+        if (holding == 0 || lp >= holding) {
+            return 0;
+        }
+        return mulDiv(holding - lp, SCALE, holding);
+    }
+
+    /// @notice Banded payout: `payoutRatioBps` of the loss between the threshold and the cap
+    function calculatePayout(
+        uint256 il,
+        uint256 holding,
+        uint256 thresholdBps,
+        uint256 upperCapBps,
+        uint256 payoutRatioBps
+    ) internal pure returns (uint256) {
+        uint256 threshold = mulDiv(thresholdBps, SCALE, BPS_DENOMINATOR);
+        uint256 upperCap = mulDiv(upperCapBps, SCALE, BPS_DENOMINATOR);
+        uint256 capped = il > upperCap ? upperCap : il;
+        if (capped <= threshold) {
+            return 0;
+        }
+        uint256 loss = mulDiv(holding, capped - threshold, SCALE);
+        return mulDiv(loss, payoutRatioBps, BPS_DENOMINATOR);
+    }
+
+    /// @notice The position's share of the pool, at most 100%
+    function userShare(uint256 lpAmount, uint256 lpTotalSupply) internal pure returns (uint256) {
+        if (lpTotalSupply == 0) {
+            return 0;
+        }
+        uint256 share = mulDiv(lpAmount, SCALE, lpTotalSupply);
+        return share > SCALE ? SCALE : share;
+    }
+
+    /// @notice Value of the position's share of the reserves
+    function lpValue(
+        uint256 reserveA,
+        uint256 reserveB,
+        uint256 lpTotalSupply,
+        uint256 priceA,
+        uint256 priceB,
+        uint256 lpAmount
+    ) internal pure returns (uint256) {
+        uint256 share = userShare(lpAmount, lpTotalSupply);
+        uint256 valueA = mulDiv(mulDiv(reserveA, share, SCALE), priceA, SCALE);
+        uint256 valueB = mulDiv(mulDiv(reserveB, share, SCALE), priceB, SCALE);
+        return valueA + valueB;
+    }
+
+    /// @notice Value of the original deposit had it simply been held
+    function holdingValue(uint256 originalA, uint256 originalB, uint256 priceA, uint256 priceB)
+        internal
+        pure
+        returns (uint256)
+    {
+        return mulDiv(originalA, priceA, SCALE) + mulDiv(originalB, priceB, SCALE);
+    }
+
+    /// @notice floor(x * y / denominator) with full 512-bit precision; reverts if the result
+    /// overflows uint256 or the denominator is zero (Remco Bloemen, MIT)
+    function mulDiv(uint256 x, uint256 y, uint256 denominator) internal pure returns (uint256 result) {
+        unchecked {
+            uint256 prod0 = x * y;
+            uint256 prod1;
+            assembly {
+                let mm := mulmod(x, y, not(0))
+                prod1 := sub(sub(mm, prod0), lt(mm, prod0))
+            }
+            if (prod1 == 0) {
+                return prod0 / denominator;
+            }
+            require(denominator > prod1, "mulDiv overflow");
+
+            uint256 remainder;
+            assembly {
+                remainder := mulmod(x, y, denominator)
+                prod1 := sub(prod1, gt(remainder, prod0))
+                prod0 := sub(prod0, remainder)
+            }
+
+            uint256 twos = denominator & (0 - denominator);
+            assembly {
+                denominator := div(denominator, twos)
+                prod0 := div(prod0, twos)
+                twos := add(div(sub(0, twos), twos), 1)
+            }
+            prod0 |= prod1 * twos;
+
+            uint256 inverse = (3 * denominator) ^ 2;
+            inverse *= 2 - denominator * inverse;
+            inverse *= 2 - denominator * inverse;
+            inverse *= 2 - denominator * inverse;
+            inverse *= 2 - denominator * inverse;
+            inverse *= 2 - denominator * inverse;
+            inverse *= 2 - denominator * inverse;
+            result = prod0 * inverse;
+        }
+    }
+}
@@ -0,0 +1,99 @@
+// abi_export.rs
+// Solidity declarations `cargo stylus export-abi` doesn't generate from the public methods: the
+// contract's events and custom errors, and the structs its `bytes` views and payloads encode.
+use crate::{claim_inputs, crosschain, migration, state_view};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use alloy_sol_types::SolStruct;
+
+/// Expand event or error declarations with `sol!`, and keep the Solidity source of each one in
+/// the `$source` constant so the exported ABI can print them verbatim
+macro_rules! sol_declarations {
+    (const $source:ident; $($(#[$($attr:tt)*])* $kind:ident $name:ident ($($param:tt)*);)*) => {
+        alloy_sol_types::sol! {
+            $($(#[$($attr)*])* $kind $name($($param)*);)*
+        }
+        pub const $source: &[&str] = &[
+            $(concat!(stringify!($kind), " ", stringify!($name), "(", stringify!($($param)*), ");")),*
+        ];
+    };
+}
+
+/// Structs carried in `bytes` by views, events and cross-chain payloads, as EIP-712 root types
+fn struct_root_types() -> [Cow<'static, str>; 11] {
+    [
+        claim_inputs::ClaimInputs::eip712_root_type(),
+        state_view::PolicyState::eip712_root_type(),
+        state_view::MarketState::eip712_root_type(),
+        state_view::PositionState::eip712_root_type(),
+        state_view::ClaimsState::eip712_root_type(),
+        state_view::AdminState::eip712_root_type(),
+        state_view::FullState::eip712_root_type(),
+        crosschain::RemoteClaim::eip712_root_type(),
+        migration::StateHeader::eip712_root_type(),
+        migration::ClaimRecord::eip712_root_type(),
+        migration::ClaimsPage::eip712_root_type(),
+    ]
+}
+
+/// Solidity struct declaration from an EIP-712 root type, e.g. `S(uint256 a,bool b)`
+pub fn solidity_struct(root_type: &str) -> String {
+    let (name, fields) = root_type.split_once('(').unwrap_or((root_type, ")"));
+    let fields = fields.trim_end_matches(')');
+    let mut declaration = format!("struct {name} {{");
+    for field in fields.split(',').filter(|field| !field.is_empty()) {
+        declaration.push_str(&format!(" {field};"));
+    }
+    declaration.push_str(" }");
+    declaration
+}
+
+/// Every event, error and struct declaration, in that order
+pub fn type_declarations() -> Vec<String> {
+    let mut declarations: Vec<String> = crate::EVENT_DECLARATIONS
+        .iter()
+        .chain(crate::ERROR_DECLARATIONS)
+        .map(|declaration| String::from(*declaration))
+        .collect();
+    declarations.extend(struct_root_types().iter().map(|root_type| solidity_struct(root_type)));
+    declarations
+}
+
+/// Print the declarations as an interface, to follow the generated function interface
+#[cfg(feature = "export-abi")]
+pub fn print_types() {
+    println!();
+    println!("interface IILInsuranceTypes {{");
+    for declaration in type_declarations() {
+        println!("    {declaration}");
+    }
+    println!("}}");
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_type_declarations() {
+        let declarations = type_declarations();
+        let find = |prefix: &str| declarations.iter().find(|declaration| declaration.starts_with(prefix)).cloned();
+
+        // Events keep their indexed markers and parameter names
+        let queued = find("event ClaimQueued(").unwrap();
+        assert!(queued.contains("uint256 indexed claimId"), "{queued}");
+        assert!(queued.ends_with(");"));
+        assert_eq!(find("error AlreadyInitialized(").unwrap(), "error AlreadyInitialized();");
+        assert!(find("error StaleRound(").unwrap().contains("uint256 lastRound"));
+        assert_eq!(
+            find("struct RemoteClaim").unwrap(),
+            "struct RemoteClaim { address claimant; uint256 minPayout; uint256 nonce; bytes signature; }"
+        );
+        assert!(find("struct FullState").unwrap().contains("PolicyState policy;"));
+        assert_eq!(
+            declarations.len(),
+            crate::EVENT_DECLARATIONS.len() + crate::ERROR_DECLARATIONS.len() + 11
+        );
+
+        assert_eq!(solidity_struct("Empty()"), "struct Empty { }");
+    }
+}
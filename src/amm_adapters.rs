@@ -0,0 +1,194 @@
+// amm_adapters.rs
+// Pair readers for the AMMs pool state can be synced from. Each adapter turns a pair's own
+// reserve and supply getters into raw (reserve0, reserve1, LP supply) amounts.
+use crate::interfaces::{ICamelotPair, IUniswapV2Pair, IUniswapV3Pool};
+use crate::util::{exp_wad, mul_div_u256};
+use alloc::vec::Vec;
+use stylus_sdk::{alloy_primitives::{Address, U256}, call::Call, prelude::*};
+
+/// Canonical Uniswap V2 pair and exact forks (SushiSwap, ...)
+pub const ADAPTER_UNISWAP_V2: u8 = 0;
+/// Camelot pair: directional fees in `getReserves`, and a stable-swap mode
+pub const ADAPTER_CAMELOT: u8 = 1;
+/// Uniswap V3 pool: prices only, read from its `observe` tick accumulator
+pub const ADAPTER_UNISWAP_V3: u8 = 2;
+
+/// ln(1.0001) scaled by 1e18: a V3 tick is a 0.01% price step
+const LN_TICK_BASE_WAD: i128 = 99_995_000_333_308;
+
+/// Raw pair state, in each token's own decimals
+pub struct PairState {
+    pub reserve0: U256,
+    pub reserve1: U256,
+    pub total_supply: U256,
+}
+
+/// Whether `adapter` is one of the ADAPTER_* kinds
+pub fn is_supported(adapter: u8) -> bool {
+    adapter <= ADAPTER_UNISWAP_V3
+}
+
+/// Read a pair's reserves and LP supply through its adapter
+/// None if a call fails, or the pair isn't constant-product (Camelot stable-swap pairs, V3 pools)
+pub fn read_pair(host: &impl HostAccess, adapter: u8, pair: Address) -> Option<PairState> {
+    match adapter {
+        ADAPTER_UNISWAP_V2 => {
+            let contract = IUniswapV2Pair::new(pair);
+            let (reserve0, reserve1, _) = contract.get_reserves(host.vm(), Call::new()).ok()?;
+            let total_supply = contract.total_supply(host.vm(), Call::new()).ok()?;
+            Some(PairState {
+                reserve0: U256::from(reserve0.to::<u128>()),
+                reserve1: U256::from(reserve1.to::<u128>()),
+                total_supply,
+            })
+        }
+        ADAPTER_CAMELOT => {
+            let contract = ICamelotPair::new(pair);
+            // Stable pairs trade on a different curve, so constant-product IL doesn't apply
+            if contract.stable_swap(host.vm(), Call::new()).ok()? {
+                return None;
+            }
+            let (reserve0, reserve1, _, _) = contract.get_reserves(host.vm(), Call::new()).ok()?;
+            let total_supply = contract.total_supply(host.vm(), Call::new()).ok()?;
+            Some(PairState {
+                reserve0: U256::from(reserve0.to::<u128>()),
+                reserve1: U256::from(reserve1.to::<u128>()),
+                total_supply,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Uniswap V2 cumulative price of token A in token B (UQ112x112 * seconds) as of `now`,
+/// extended past the pair's last update at its current spot price, like UniswapV2OracleLibrary
+pub fn read_cumulative_price(host: &impl HostAccess, pair: Address, token_a_is_token1: bool, now: u64) -> Option<U256> {
+    let contract = IUniswapV2Pair::new(pair);
+    let cumulative = if token_a_is_token1 {
+        contract.price1_cumulative_last(host.vm(), Call::new()).ok()?
+    } else {
+        contract.price0_cumulative_last(host.vm(), Call::new()).ok()?
+    };
+    let (reserve0, reserve1, last_update) = contract.get_reserves(host.vm(), Call::new()).ok()?;
+    let (reserve_a, reserve_b) = if token_a_is_token1 {
+        (U256::from(reserve1.to::<u128>()), U256::from(reserve0.to::<u128>()))
+    } else {
+        (U256::from(reserve0.to::<u128>()), U256::from(reserve1.to::<u128>()))
+    };
+    
+    // The pair keeps 32-bit timestamps and lets cumulatives overflow; differences stay correct
+    let elapsed = (now as u32).wrapping_sub(last_update);
+    if elapsed == 0 || reserve_a == U256::ZERO {
+        return Some(cumulative);
+    }
+    let spot = (reserve_b << 112) / reserve_a;
+    Some(cumulative.wrapping_add(spot.wrapping_mul(U256::from(elapsed))))
+}
+
+/// Average price of token A in token B (scaled by 1e18) between two cumulative observations
+/// `decimals_a` and `decimals_b` undo the raw reserve ratio's decimal mismatch
+pub fn twap_from_cumulatives(
+    cumulative_start: U256,
+    cumulative_end: U256,
+    elapsed: U256,
+    decimals_a: u8,
+    decimals_b: u8,
+) -> U256 {
+    if elapsed == U256::ZERO {
+        return U256::ZERO;
+    }
+    let average = cumulative_end.wrapping_sub(cumulative_start) / elapsed;
+    let ten = U256::from(10u8);
+    let price = mul_div_u256(average, ten.pow(U256::from(18 + decimals_a as u64)), U256::from(1u8) << 112);
+    price / ten.pow(U256::from(decimals_b))
+}
+
+/// Arithmetic mean tick of a Uniswap V3 pool over the last `window` seconds, rounded toward
+/// negative infinity like the V3 OracleLibrary
+pub fn read_v3_mean_tick(host: &impl HostAccess, pool: Address, window: u32) -> Option<i64> {
+    if window == 0 {
+        return None;
+    }
+    let seconds_agos: Vec<u32> = vec![window, 0];
+    let (tick_cumulatives, _) = IUniswapV3Pool::new(pool).observe(host.vm(), Call::new(), seconds_agos).ok()?;
+    let start = i64::try_from(*tick_cumulatives.first()?).ok()?;
+    let end = i64::try_from(*tick_cumulatives.get(1)?).ok()?;
+
+    let delta = end - start;
+    let window = i64::from(window);
+    let mut tick = delta / window;
+    if delta < 0 && delta % window != 0 {
+        tick -= 1;
+    }
+    Some(tick)
+}
+
+/// Price of token A in token B (scaled by 1e18) at a V3 tick, where 1.0001^tick is token1 per token0
+/// `decimals_a` and `decimals_b` undo the raw price's decimal mismatch
+pub fn price_from_tick(tick: i64, token_a_is_token1: bool, decimals_a: u8, decimals_b: u8) -> U256 {
+    let exponent = i128::from(tick) * LN_TICK_BASE_WAD;
+    let raw = exp_wad(if token_a_is_token1 { -exponent } else { exponent });
+    let ten = U256::from(10u8);
+    mul_div_u256(raw, ten.pow(U256::from(decimals_a)), ten.pow(U256::from(decimals_b)))
+}
+
+/// Rescale a raw token amount with `decimals` decimals to 18 decimals
+pub fn to_wad(amount: U256, decimals: u8) -> U256 {
+    if decimals <= 18 {
+        amount.saturating_mul(U256::from(10u8).pow(U256::from(18 - decimals)))
+    } else {
+        amount / U256::from(10u8).pow(U256::from(decimals - 18))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_to_wad() {
+        // 1000 USDC (6 decimals), 1 WETH (18), 1 token with 24 decimals
+        assert_eq!(to_wad(U256::from(1_000_000_000u64), 6), U256::from(1000u32) * U256::from(10u8).pow(U256::from(18u8)));
+        assert_eq!(to_wad(U256::from(10u8).pow(U256::from(18u8)), 18), U256::from(10u8).pow(U256::from(18u8)));
+        assert_eq!(to_wad(U256::from(10u8).pow(U256::from(24u8)), 24), U256::from(10u8).pow(U256::from(18u8)));
+        assert!(!is_supported(ADAPTER_UNISWAP_V3 + 1));
+    }
+
+    #[test]
+    fn test_price_from_tick() {
+        let wad = U256::from(10u8).pow(U256::from(18u8));
+        assert_eq!(price_from_tick(0, false, 18, 18), wad);
+
+        // 1.0001^76012 ~= 1999.835
+        let price = price_from_tick(76_012, false, 18, 18);
+        let expected = U256::from(1_999_835_018_390_134_600_000u128);
+        assert!(price.abs_diff(expected) < wad / U256::from(1_000_000u32), "price = {price}");
+
+        // Same pool seen from token1: the inverse
+        let inverse = price_from_tick(76_012, true, 18, 18);
+        assert!(mul_div_u256(price, inverse, wad).abs_diff(wad) < U256::from(1_000_000u32));
+
+        // USDC (6) / WETH (18) pool with A = WETH = token1: tick 200311 ~= $2000.04
+        let weth = price_from_tick(200_311, true, 18, 6);
+        let expected = U256::from(2_000_040_289_652_500_000_000u128);
+        assert!(weth.abs_diff(expected) < wad / U256::from(1_000u32), "weth = {weth}");
+    }
+
+    #[test]
+    fn test_twap_from_cumulatives() {
+        let wad = U256::from(10u8).pow(U256::from(18u8));
+        // 1h at 2000 then 1h at 2200 (UQ112x112): average 2100
+        let start = U256::from(123u8);
+        let end = start + (U256::from(2000u32) << 112) * U256::from(3600u32) + (U256::from(2200u32) << 112) * U256::from(3600u32);
+        assert_eq!(twap_from_cumulatives(start, end, U256::from(7200u32), 18, 18), U256::from(2100u32) * wad);
+
+        // WETH (18) priced in USDC (6): raw ratio 2000e6 / 1e18, normalized back to 2000
+        let raw = ((U256::from(2000u32) * U256::from(1_000_000u32)) << 112) / wad;
+        let twap = twap_from_cumulatives(U256::ZERO, raw * U256::from(60u8), U256::from(60u8), 18, 6);
+        assert!(twap.abs_diff(U256::from(2000u32) * wad) < U256::from(1_000_000u32), "twap = {twap}");
+
+        // Wrapped cumulatives still difference correctly
+        let wrapped_end = U256::MAX.wrapping_add((U256::from(2000u32) << 112) * U256::from(10u8) + U256::from(1u8));
+        assert_eq!(twap_from_cumulatives(U256::MAX, wrapped_end, U256::from(10u8), 18, 18), U256::from(2000u32) * wad);
+    }
+}
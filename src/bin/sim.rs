@@ -0,0 +1,178 @@
+//! Deterministic IL/payout simulator for product and actuarial analysis.
+//!
+//! Loads a JSON scenario (pool reserves, prices, positions, policy) and prints each position's
+//! LP value, holding value, IL and payout, computed by the same `lp_valuator` and contract
+//! payout code the deployed contract runs. Amounts are human-readable decimals (strings or
+//! numbers), scaled to 1e18 on load. Run with:
+//!
+//! ```bash
+//! cargo run --features sim --bin sim -- scenarios/demo.json
+//! ```
+
+use alloy_primitives::U256;
+use serde_json::Value;
+use stylus_hello_world::{compute_values, params, ILInsurance, PolicyParams};
+
+const DECIMALS: usize = 18;
+
+/// Outcome for one position of the scenario
+#[derive(Debug, PartialEq, Eq)]
+struct PositionResult {
+    name: String,
+    lp_value: U256,
+    holding_value: U256,
+    il: U256,
+    payout: U256,
+}
+
+/// Parse a decimal amount ("1.5", 1.5 or 2000) into a 1e18-scaled integer
+fn parse_wad(value: &Value) -> Result<U256, String> {
+    let text = match value {
+        Value::String(text) => text.clone(),
+        Value::Number(number) => number.to_string(),
+        other => return Err(format!("expected a decimal amount, found {other}")),
+    };
+    let (whole, fraction) = text.split_once('.').unwrap_or((&text, ""));
+    if fraction.len() > DECIMALS || !format!("{whole}{fraction}").bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("invalid amount {text:?}"));
+    }
+    let digits = format!("{whole}{fraction:0<DECIMALS$}");
+    U256::from_str_radix(&digits, 10).map_err(|_| format!("invalid amount {text:?}"))
+}
+
+/// Render a 1e18-scaled integer with 6 decimals
+fn format_wad(value: U256) -> String {
+    let scale = U256::from(10u8).pow(U256::from(DECIMALS));
+    let micros = (value % scale) / U256::from(10u8).pow(U256::from(DECIMALS - 6));
+    format!("{}.{:0>6}", value / scale, micros)
+}
+
+fn field<'a>(object: &'a Value, key: &str) -> Result<&'a Value, String> {
+    object.get(key).ok_or_else(|| format!("missing field {key:?}"))
+}
+
+fn bps(object: &Value, key: &str) -> Result<U256, String> {
+    field(object, key)?.as_u64().map(U256::from).ok_or_else(|| format!("{key:?} must be an integer (bps)"))
+}
+
+/// Value every position of a scenario
+fn simulate(scenario: &Value) -> Result<Vec<PositionResult>, String> {
+    let policy = field(scenario, "policy")?;
+    let policy = PolicyParams::new(
+        bps(policy, "threshold_bps")?,
+        bps(policy, "upper_cap_bps")?,
+        bps(policy, "payout_ratio_bps")?,
+    );
+    params::validate_policy(&policy).map_err(|error| format!("invalid policy: {error:?}"))?;
+
+    let pool = field(scenario, "pool")?;
+    let prices = field(scenario, "prices")?;
+    let reserve_a = parse_wad(field(pool, "reserve_a")?)?;
+    let reserve_b = parse_wad(field(pool, "reserve_b")?)?;
+    let total_supply = parse_wad(field(pool, "lp_total_supply")?)?;
+    let price_a = parse_wad(field(prices, "a")?)?;
+    let price_b = parse_wad(field(prices, "b")?)?;
+    let to_u128 = |value: U256| u128::try_from(value).map_err(|_| format!("amount {value} is out of range"));
+
+    let positions = field(scenario, "positions")?.as_array().ok_or("\"positions\" must be an array")?;
+    let mut results = Vec::new();
+    for (index, position) in positions.iter().enumerate() {
+        let name = position.get("name").and_then(Value::as_str).map_or_else(|| format!("#{index}"), String::from);
+        let lp_amount = parse_wad(field(position, "lp_amount")?)?;
+        let original_a = parse_wad(field(position, "original_a")?)?;
+        let original_b = parse_wad(field(position, "original_b")?)?;
+
+        let (lp_value, holding_value, _) = compute_values(
+            (to_u128(reserve_a)?, to_u128(reserve_b)?),
+            to_u128(total_supply)?,
+            to_u128(lp_amount)?,
+            (to_u128(price_a)?, to_u128(price_b)?),
+            (to_u128(original_a)?, to_u128(original_b)?),
+        );
+        let il = ILInsurance::calc_il(
+            reserve_a, reserve_b, total_supply, price_a, price_b, lp_amount, original_a, original_b,
+        );
+        let payout = ILInsurance::calc_payout(
+            il,
+            U256::from(holding_value),
+            policy.threshold_bps,
+            policy.upper_cap_bps,
+            policy.payout_ratio_bps,
+        );
+        results.push(PositionResult {
+            name,
+            lp_value: U256::from(lp_value),
+            holding_value: U256::from(holding_value),
+            il,
+            payout,
+        });
+    }
+    Ok(results)
+}
+
+fn run(path: &str) -> Result<(), String> {
+    let text = std::fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?;
+    let scenario: Value = serde_json::from_str(&text).map_err(|error| format!("{path}: {error}"))?;
+
+    println!("{:<16} {:>20} {:>20} {:>10} {:>20}", "position", "lp value", "holding value", "il %", "payout");
+    for result in simulate(&scenario)? {
+        let il_percent = format_wad(result.il * U256::from(100u8));
+        println!(
+            "{:<16} {:>20} {:>20} {:>10} {:>20}",
+            result.name,
+            format_wad(result.lp_value),
+            format_wad(result.holding_value),
+            &il_percent[..il_percent.len() - 4],
+            format_wad(result.payout),
+        );
+    }
+    Ok(())
+}
+
+fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("usage: sim <scenario.json>");
+        std::process::exit(2);
+    };
+    if let Err(error) = run(&path) {
+        eprintln!("error: {error}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn wad(value: u64) -> U256 {
+        U256::from(value) * U256::from(10u8).pow(U256::from(DECIMALS))
+    }
+
+    #[test]
+    fn test_parse_and_format() {
+        assert_eq!(parse_wad(&Value::from("2000")).unwrap(), wad(2000));
+        assert_eq!(parse_wad(&serde_json::json!(0.5)).unwrap(), wad(1) / U256::from(2u8));
+        assert!(parse_wad(&Value::from("1.2.3")).is_err());
+        assert!(parse_wad(&Value::from("-1")).is_err());
+        assert_eq!(format_wad(wad(320)), "320.000000");
+        assert_eq!(format_wad(wad(1) / U256::from(2u8)), "0.500000");
+    }
+
+    #[test]
+    fn test_demo_scenario() {
+        let scenario: Value = serde_json::from_str(include_str!("../../scenarios/demo.json")).unwrap();
+        let results = simulate(&scenario).unwrap();
+        assert_eq!(
+            results[0],
+            PositionResult {
+                name: "demo".into(),
+                lp_value: wad(2000),
+                holding_value: wad(4000),
+                il: wad(1) / U256::from(2u8),
+                payout: wad(320),
+            }
+        );
+        // Entered at today's prices: no IL, no payout
+        assert_eq!((results[1].il, results[1].payout), (U256::ZERO, U256::ZERO));
+    }
+}
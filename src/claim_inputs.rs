@@ -0,0 +1,35 @@
+// claim_inputs.rs
+// Full input set a claim's payout is derived from. Claims record the keccak256 of its ABI encoding,
+// so anyone holding the emitted inputs can recompute the hash and the payout off-chain.
+use alloy_sol_types::{sol, SolValue};
+use stylus_sdk::alloy_primitives::{keccak256, FixedBytes};
+
+sol! {
+    /// Pool state, prices, position and policy at claim time, and the IL earlier claims on the
+    /// position already covered (the payout is the banded loss above it)
+    /// With `storedValuation` the LP side is valued from `lpUnitValue` (the per-LP value stored at
+    /// the last sync) instead of the reserves; the reserves still value nothing else
+    struct ClaimInputs {
+        uint256 reserveA;
+        uint256 reserveB;
+        uint256 lpTotalSupply;
+        uint256 priceA;
+        uint256 priceB;
+        uint256 quotePriceUsd;
+        uint256 lpAmount;
+        uint256 originalA;
+        uint256 originalB;
+        uint8 benchmark;
+        uint256 thresholdBps;
+        uint256 upperCapBps;
+        uint256 payoutRatioBps;
+        bool storedValuation;
+        uint256 lpUnitValue;
+        uint256 claimedIl;
+    }
+}
+
+/// keccak256 of the ABI-encoded inputs
+pub fn inputs_hash(inputs: &ClaimInputs) -> FixedBytes<32> {
+    keccak256(inputs.abi_encode())
+}
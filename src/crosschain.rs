@@ -0,0 +1,135 @@
+// crosschain.rs
+// Wire format of claim requests arriving over LayerZero. Each request carries the claimant's
+// signature over `remote_claim_digest`, so a compromised or buggy peer can't claim for someone
+// else; this side also trusts messages from configured peers only, delivered by the configured endpoint.
+// Relayed pool state is authenticated the same way: relayers sign `pool_state_digest`.
+use alloc::vec::Vec;
+use alloy_sol_types::{sol, SolValue};
+use stylus_sdk::alloy_primitives::{keccak256, Address, FixedBytes, U256};
+
+/// The ecrecover precompile
+pub const ECRECOVER: Address = Address::with_last_byte(1);
+
+sol! {
+    /// A claim requested from another chain: the payout is queued for `claimant` here and
+    /// credited to its withdrawable balance on this chain. `signature` is the claimant's 65-byte
+    /// r || s || v signature over `remote_claim_digest`, and `nonce` its next remote claim nonce
+    struct RemoteClaim {
+        address claimant;
+        uint256 minPayout;
+        uint256 nonce;
+        bytes signature;
+    }
+}
+
+/// LayerZero's 32-byte form of an EVM address (left-padded)
+pub fn address_to_bytes32(address: Address) -> FixedBytes<32> {
+    address.into_word()
+}
+
+/// Digest a remote claim is signed over: the EIP-191 (`personal_sign`) hash of
+/// keccak256(abi.encode(receiver, chainId, claimant, minPayout, nonce)), so a signature is only
+/// good for one request to the insurance contract `receiver` on chain `chain_id`
+pub fn remote_claim_digest(
+    receiver: Address,
+    chain_id: u64,
+    claimant: Address,
+    min_payout: U256,
+    nonce: U256,
+) -> FixedBytes<32> {
+    let message = keccak256((receiver, U256::from(chain_id), claimant, min_payout, nonce).abi_encode());
+    eth_signed_message_hash(message)
+}
+
+/// Digest a relayer signs to report a remote pool's state: the EIP-191 hash of
+/// keccak256(abi.encode(receiver, chainId, round, reserveA, reserveB, totalSupply))
+pub fn pool_state_digest(
+    receiver: Address,
+    chain_id: u64,
+    round: U256,
+    reserve_a: U256,
+    reserve_b: U256,
+    total_supply: U256,
+) -> FixedBytes<32> {
+    let message = keccak256((receiver, U256::from(chain_id), round, reserve_a, reserve_b, total_supply).abi_encode());
+    eth_signed_message_hash(message)
+}
+
+/// keccak256("\x19Ethereum Signed Message:\n32" || hash)
+pub fn eth_signed_message_hash(hash: FixedBytes<32>) -> FixedBytes<32> {
+    keccak256([b"\x19Ethereum Signed Message:\n32".as_slice(), hash.as_slice()].concat())
+}
+
+/// Input to the ecrecover precompile for a 65-byte r || s || v `signature` over `digest`:
+/// digest, v, r and s as 32-byte words. None if the signature is malformed
+pub fn ecrecover_input(digest: FixedBytes<32>, signature: &[u8]) -> Option<Vec<u8>> {
+    if signature.len() != 65 {
+        return None;
+    }
+    let v = match signature[64] {
+        0 | 1 => signature[64] + 27,
+        27 | 28 => signature[64],
+        _ => return None,
+    };
+    let mut input = Vec::with_capacity(128);
+    input.extend_from_slice(digest.as_slice());
+    input.extend_from_slice(&[0u8; 31]);
+    input.push(v);
+    input.extend_from_slice(&signature[..64]);
+    Some(input)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_remote_claim_round_trip() {
+        let request = RemoteClaim {
+            claimant: Address::repeat_byte(0x42),
+            minPayout: U256::from(300u32),
+            nonce: U256::from(1u8),
+            signature: vec![0x5a; 65].into(),
+        };
+        let decoded = RemoteClaim::abi_decode(&request.abi_encode(), true).unwrap();
+        assert_eq!(decoded.claimant, Address::repeat_byte(0x42));
+        assert_eq!(decoded.minPayout, U256::from(300u32));
+        assert_eq!(decoded.nonce, U256::from(1u8));
+        assert_eq!(decoded.signature, request.signature);
+
+        let word = address_to_bytes32(Address::repeat_byte(0x42));
+        assert_eq!(&word[..12], &[0u8; 12]);
+        assert_eq!(Address::from_word(word), Address::repeat_byte(0x42));
+    }
+
+    #[test]
+    fn test_ecrecover_input() {
+        let digest = FixedBytes::<32>::repeat_byte(0xd1);
+        let mut signature = [0x11u8; 65];
+        signature[32..64].fill(0x22);
+        signature[64] = 1;
+
+        let input = ecrecover_input(digest, &signature).unwrap();
+        assert_eq!(input.len(), 128);
+        assert_eq!(&input[..32], digest.as_slice());
+        assert_eq!(U256::from_be_slice(&input[32..64]), U256::from(28u8));
+        assert_eq!(&input[64..96], &[0x11u8; 32]);
+        assert_eq!(&input[96..], &[0x22u8; 32]);
+
+        signature[64] = 29;
+        assert!(ecrecover_input(digest, &signature).is_none());
+        assert!(ecrecover_input(digest, &signature[..64]).is_none());
+
+        // Each field of the request changes the digest
+        let (receiver, claimant) = (Address::repeat_byte(0x01), Address::repeat_byte(0x42));
+        let base = remote_claim_digest(receiver, 42_161, claimant, U256::from(300u32), U256::ZERO);
+        assert_ne!(base, remote_claim_digest(receiver, 1, claimant, U256::from(300u32), U256::ZERO));
+        assert_ne!(base, remote_claim_digest(receiver, 42_161, claimant, U256::from(300u32), U256::from(1u8)));
+        assert_ne!(base, remote_claim_digest(Address::repeat_byte(0x02), 42_161, claimant, U256::from(300u32), U256::ZERO));
+
+        // Likewise for each field of a pool state report
+        let state = pool_state_digest(receiver, 42_161, U256::from(1u8), U256::from(2u8), U256::from(3u8), U256::from(4u8));
+        assert_ne!(state, pool_state_digest(receiver, 42_161, U256::from(2u8), U256::from(2u8), U256::from(3u8), U256::from(4u8)));
+        assert_ne!(state, pool_state_digest(receiver, 1, U256::from(1u8), U256::from(2u8), U256::from(3u8), U256::from(4u8)));
+    }
+}
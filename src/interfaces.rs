@@ -0,0 +1,64 @@
+// interfaces.rs
+// Solidity interfaces of the external contracts the insurance contract reads from or pays through
+use stylus_sdk::prelude::*;
+
+sol_interface! {
+    /// Chainlink price feed
+    interface IAggregatorV3 {
+        function decimals() external view returns (uint8);
+        function latestRoundData() external view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound);
+    }
+
+    /// ERC-20 token (payout token, LP token)
+    interface IERC20 {
+        function totalSupply() external view returns (uint256);
+        function balanceOf(address account) external view returns (uint256);
+        function transfer(address to, uint256 amount) external returns (bool);
+        function transferFrom(address from, address to, uint256 amount) external returns (bool);
+    }
+
+    /// Uniswap V2 pair
+    interface IUniswapV2Pair {
+        function token0() external view returns (address);
+        function token1() external view returns (address);
+        function totalSupply() external view returns (uint256);
+        function getReserves() external view returns (uint112 reserve0, uint112 reserve1, uint32 blockTimestampLast);
+        function price0CumulativeLast() external view returns (uint256);
+        function price1CumulativeLast() external view returns (uint256);
+    }
+
+    /// Uniswap V3 pool (price oracle only: positions there have no fungible LP supply)
+    interface IUniswapV3Pool {
+        function observe(uint32[] secondsAgos) external view returns (int56[] tickCumulatives, uint160[] secondsPerLiquidityCumulativeX128s);
+    }
+
+    /// Camelot pair: `getReserves` also returns the per-direction fees; stable pairs use a different curve
+    interface ICamelotPair {
+        function totalSupply() external view returns (uint256);
+        function getReserves() external view returns (uint112 reserve0, uint112 reserve1, uint16 token0FeePercent, uint16 token1FeePercent);
+        function stableSwap() external view returns (bool);
+    }
+
+    /// Reinsurer taking the layer of losses above the contract's retention
+    interface IReinsurer {
+        function cede(uint256 premiumShare) external;
+        function recover(uint256 lossAmount) external returns (uint256);
+    }
+
+    /// Protocol-token staking registry consulted for premium discounts
+    interface IStakingRegistry {
+        function stakedBalance(address account) external view returns (uint256);
+    }
+
+    /// ERC-1822 proxiable implementation (UUPS upgrade target)
+    interface IERC1822Proxiable {
+        function proxiableUUID() external view returns (bytes32);
+    }
+
+    /// Integrator hooks notified of position and claim lifecycle events (best-effort)
+    interface IILInsuranceHooks {
+        function onPositionRegistered(address holder, address pool, uint256 lpAmount) external;
+        function onClaimProcessed(uint256 claimId, address claimant, uint256 amount) external;
+        function onPolicyExpired(address holder, address pool) external;
+    }
+}
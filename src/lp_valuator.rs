@@ -1,34 +1,53 @@
 // lp_valuator.rs
+#[cfg(feature = "demo")]
 use crate::constant::*;
 use crate::util::SCALE;
 use crate::util::*;
 
+/// Value an LP position against the holding benchmark.
+///
+/// `reserves`, `prices` and `originals` are (token A, token B) pairs; every amount and
+/// price is scaled by SCALE. Returns (lp_value, holding_value, il_frac), all scaled by SCALE.
+pub fn compute_values(
+    reserves: (u128, u128),
+    total_supply: u128,
+    user_lp: u128,
+    prices: (u128, u128),
+    originals: (u128, u128),
+) -> (u128 /*lp_value*/, u128 /*holding_value*/, u128 /*il_frac*/) {
+    compute_values_multi(
+        &[reserves.0, reserves.1],
+        total_supply,
+        user_lp,
+        &[prices.0, prices.1],
+        &[originals.0, originals.1],
+    )
+}
 
-pub fn compute_values_from_constants() -> (u128 /*lp_value*/, u128 /*holding_value*/, u128 /*il_frac*/) {
-    // read constants
-    let reserve_eth = PAIR_A_RESERVE_ETH;
-    let reserve_usdc = PAIR_A_RESERVE_USDC;
-    let total_supply = PAIR_A_LP_TOTAL_SUPPLY;
-    let user_lp = USER_LP_AMOUNT;
-
+/// Value an LP position in a pool with any number of tokens (e.g. Curve tricrypto).
+///
+/// `reserves`, `prices` and `originals` hold one entry per token and must have the same
+/// length; extra entries in a longer slice are ignored. Scaling as in [`compute_values`].
+pub fn compute_values_multi(
+    reserves: &[u128],
+    total_supply: u128,
+    user_lp: u128,
+    prices: &[u128],
+    originals: &[u128],
+) -> (u128 /*lp_value*/, u128 /*holding_value*/, u128 /*il_frac*/) {
     // user share: user_lp / total_supply (both already scaled, so result is fraction scaled by SCALE)
-    let user_share = mul_div(user_lp, SCALE, total_supply); // scaled by SCALE
+    let user_share = user_share_scaled(user_lp, total_supply); // scaled by SCALE, at most 100%
 
-    // current underlying token amounts for the user
-    let current_eth = mul_div(reserve_eth, user_share, SCALE);
-    let current_usdc = mul_div(reserve_usdc, user_share, SCALE);
+    let mut lp_value_usd = 0;
+    let mut holding_value_usd = 0;
+    for ((&reserve, &price), &original) in reserves.iter().zip(prices).zip(originals) {
+        // current underlying token amount for the user, valued in USD (scaled)
+        let current = mul_div(reserve, user_share, SCALE);
+        lp_value_usd += mul_div(current, price, SCALE);
 
-    // current LP value in USD (scaled)
-    let lp_value_usd = mul_div(current_eth, PRICE_ETH_USD, SCALE)
-                     + mul_div(current_usdc, PRICE_USDC_USD, SCALE);
-
-    // holding value: use snapshot constants (for demo we can reuse same original amounts or provide different)
-    // For simplicity: assume original_a = user_share_at_buy * reserve_at_buy (we can hardcode buy snapshot)
-    // Example: snapshot reserves at buy time (hardcoded different)
-    let original_eth = 1 * SCALE; // user originally had 1 ETH (sample)
-    let original_usdc = 2000 * SCALE;
-    let holding_value_usd = mul_div(original_eth, PRICE_ETH_USD, SCALE)
-                          + mul_div(original_usdc, PRICE_USDC_USD, SCALE);
+        // holding value: what the original deposit would be worth today
+        holding_value_usd += mul_div(original, price, SCALE);
+    }
 
     // compute IL
     let diff = if holding_value_usd > lp_value_usd { holding_value_usd - lp_value_usd } else { 0 };
@@ -36,3 +55,152 @@ pub fn compute_values_from_constants() -> (u128 /*lp_value*/, u128 /*holding_val
 
     (lp_value_usd, holding_value_usd, il_frac)
 }
+
+/// Same as [`compute_values`] for prices quoted in an arbitrary asset rather than USD.
+///
+/// `quote_price_usd` is the USD price of the quote asset (scaled by SCALE). The IL fraction
+/// does not depend on the numeraire; the LP and holding values are converted to USD.
+pub fn compute_values_in_quote(
+    reserves: (u128, u128),
+    total_supply: u128,
+    user_lp: u128,
+    prices: (u128, u128),
+    originals: (u128, u128),
+    quote_price_usd: u128,
+) -> (u128 /*lp_value*/, u128 /*holding_value*/, u128 /*il_frac*/) {
+    let (lp_value, holding_value, il_frac) = compute_values(reserves, total_supply, user_lp, prices, originals);
+
+    (
+        mul_div(lp_value, quote_price_usd, SCALE),
+        mul_div(holding_value, quote_price_usd, SCALE),
+        il_frac,
+    )
+}
+
+#[cfg(feature = "demo")]
+pub fn compute_values_from_constants() -> (u128 /*lp_value*/, u128 /*holding_value*/, u128 /*il_frac*/) {
+    // For the demo the user originally deposited 1 ETH + 2000 USDC
+    compute_values(
+        (PAIR_A_RESERVE_ETH, PAIR_A_RESERVE_USDC),
+        PAIR_A_LP_TOTAL_SUPPLY,
+        USER_LP_AMOUNT,
+        (PRICE_ETH_USD, PRICE_USDC_USD),
+        (SCALE, 2000 * SCALE),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    // Keep inputs small enough that value sums cannot overflow u128
+    const MAX_AMOUNT: u128 = 1_000_000_000 * SCALE;
+    const MAX_PRICE: u128 = 1_000_000 * SCALE;
+
+    #[test]
+    fn test_known_position() {
+        // 0.1% of 500 ETH + 1M USDC at $2000/ETH against 1 ETH + 2000 USDC held
+        let (lp_value, holding_value, il_frac) = compute_values(
+            (500 * SCALE, 1_000_000 * SCALE),
+            1_000_000 * SCALE,
+            1000 * SCALE,
+            (2000 * SCALE, SCALE),
+            (SCALE, 2000 * SCALE),
+        );
+
+        assert_eq!(lp_value, 2000 * SCALE);
+        assert_eq!(holding_value, 4000 * SCALE);
+        assert_eq!(il_frac, SCALE / 2);
+    }
+
+    #[test]
+    fn test_three_token_position() {
+        // 10% of a 3000 USDT / 100 LINK / 1 WBTC pool against 300 USDT + 20 LINK + 0.1 WBTC held
+        let (lp_value, holding_value, il_frac) = compute_values_multi(
+            &[3000 * SCALE, 100 * SCALE, SCALE],
+            1000 * SCALE,
+            100 * SCALE,
+            &[SCALE, 30 * SCALE, 3000 * SCALE],
+            &[300 * SCALE, 20 * SCALE, SCALE / 10],
+        );
+
+        assert_eq!(lp_value, 900 * SCALE);
+        assert_eq!(holding_value, 1200 * SCALE);
+        assert_eq!(il_frac, SCALE / 4);
+    }
+
+    #[test]
+    fn test_eth_quoted_position() {
+        // Same position with prices in ETH (USDC = 1/2000 ETH) and ETH at $2000
+        let (lp_value, holding_value, il_frac) = compute_values_in_quote(
+            (500 * SCALE, 1_000_000 * SCALE),
+            1_000_000 * SCALE,
+            1000 * SCALE,
+            (SCALE, SCALE / 2000),
+            (SCALE, 2000 * SCALE),
+            2000 * SCALE,
+        );
+
+        assert_eq!(lp_value, 2000 * SCALE);
+        assert_eq!(holding_value, 4000 * SCALE);
+        assert_eq!(il_frac, SCALE / 2);
+    }
+
+    proptest! {
+        #[test]
+        fn il_is_a_fraction(
+            reserve_a in 0..MAX_AMOUNT,
+            reserve_b in 0..MAX_AMOUNT,
+            total_supply in 1..MAX_AMOUNT,
+            share_bps in 0u128..=10_000,
+            price_a in 0..MAX_PRICE,
+            price_b in 0..MAX_PRICE,
+            original_a in 0..MAX_AMOUNT,
+            original_b in 0..MAX_AMOUNT,
+        ) {
+            let user_lp = mul_div(total_supply, share_bps, 10_000);
+            let (lp_value, holding_value, il_frac) = compute_values(
+                (reserve_a, reserve_b),
+                total_supply,
+                user_lp,
+                (price_a, price_b),
+                (original_a, original_b),
+            );
+
+            prop_assert!(il_frac <= SCALE);
+            if lp_value >= holding_value {
+                prop_assert_eq!(il_frac, 0);
+            }
+        }
+
+        #[test]
+        fn no_il_when_holding_current_amounts(
+            reserve_a in 0..MAX_AMOUNT,
+            reserve_b in 0..MAX_AMOUNT,
+            total_supply in 1..MAX_AMOUNT,
+            share_bps in 0u128..=10_000,
+            price_a in 0..MAX_PRICE,
+            price_b in 0..MAX_PRICE,
+        ) {
+            // Originals equal to the user's current pool amounts: LP and holding values coincide
+            let user_lp = mul_div(total_supply, share_bps, 10_000);
+            let user_share = user_share_scaled(user_lp, total_supply);
+            let originals = (
+                mul_div(reserve_a, user_share, SCALE),
+                mul_div(reserve_b, user_share, SCALE),
+            );
+
+            let (lp_value, holding_value, il_frac) = compute_values(
+                (reserve_a, reserve_b),
+                total_supply,
+                user_lp,
+                (price_a, price_b),
+                originals,
+            );
+
+            prop_assert_eq!(lp_value, holding_value);
+            prop_assert_eq!(il_frac, 0);
+        }
+    }
+}
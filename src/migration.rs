@@ -0,0 +1,182 @@
+// migration.rs
+// Wire format of the paginated state export used to move the contract's state to a new implementation.
+// Page 0 is the header (policy, market and its retained history, position, epochs, governance, shutdown);
+// later pages carry the claims ledger in order, each claim with its attestation.
+//
+// Per-address and per-period mappings can't be enumerated, so they are carried for the keys the state
+// itself names: the tracked pool's status and policy override, the position holder's policy nonce, the
+// blacklist flags of the holder and of every claimant, the current payout window's total and the
+// reinsurance recovered in every epoch with a claim. Entries under any other key stay behind: pool
+// overrides and delistings of other pools, blacklist flags of accounts that never claimed, other
+// buyers' policy nonces, and totals of past payout windows (which no longer limit anything).
+//
+// Not carried, by design:
+// - escrowed LP: the tokens sit in the old contract, so exports are refused until the escrow is released
+// - open proposals and locked votes: voters withdraw their tokens from the old contract
+// - per-claim approvals: claims awaiting approval need their approvals again on the new contract
+// - remote claim nonces: signed requests name the receiving contract, so old signatures can't replay
+// - operational config (feeds, adapters, relayers, approvers, hooks, LayerZero peers, staker
+//   discount tiers, holder preferences such as alerts and auto-claim): set up again on the new contract
+use alloy_sol_types::sol;
+
+/// Claims carried per export page
+pub const CLAIMS_PER_PAGE: u64 = 16;
+
+/// Page kinds, the first word of every page
+pub const PAGE_HEADER: u8 = 0;
+pub const PAGE_CLAIMS: u8 = 1;
+
+sol! {
+    /// One retained market snapshot; the header lists them oldest first
+    struct MarketRecord {
+        uint256 timestamp;
+        uint256 reserveA;
+        uint256 reserveB;
+        uint256 lpTotalSupply;
+        uint256 priceA;
+        uint256 priceB;
+    }
+
+    /// Policy, pool, market, position, epoch, governance and shutdown configuration
+    struct StateHeader {
+        uint8 kind;
+        uint256 storageVersion;
+        uint256 thresholdBps;
+        uint256 upperCapBps;
+        uint256 payoutRatioBps;
+        uint8 benchmark;
+        address pool;
+        uint256 reserveA;
+        uint256 reserveB;
+        uint256 lpTotalSupply;
+        uint256 priceA;
+        uint256 priceB;
+        address quoteAsset;
+        uint256 quotePriceUsd;
+        uint256 lpAmount;
+        uint256 originalA;
+        uint256 originalB;
+        uint256 epochDuration;
+        uint256 epochGenesis;
+        uint256 epochPayoutBudget;
+        address positionHolder;
+        bytes32 policyId;
+        uint256 registeredAt;
+        uint256 claimedIl;
+        uint256 lastClaimAt;
+        uint256 streamThreshold;
+        uint256 streamDuration;
+        address governanceToken;
+        uint256 votingPeriod;
+        uint256 proposalQuorum;
+        uint8 poolStatus;
+        bool poolPolicyActive;
+        uint256 poolThresholdBps;
+        uint256 poolUpperCapBps;
+        uint256 poolPayoutRatioBps;
+        uint8 poolBenchmark;
+        uint256 shutdownAt;
+        uint256 settlementWindow;
+        uint256 policyNonce;
+        bool holderBlacklisted;
+        uint256 rateLimitWindow;
+        uint256 maxPayoutPerWindow;
+        uint256 paymentWindow;
+        uint256 windowPaid;
+        uint256 marketSnapshotCount;
+        MarketRecord[] marketSnapshots;
+    }
+
+    /// One queued claim, its attestation and adjustment reason, plus its claimant's withdrawable balance
+    /// and blacklist flag and the reinsurance recovered in its epoch
+    struct ClaimRecord {
+        address claimant;
+        uint256 epoch;
+        uint256 amount;
+        uint256 settledAmount;
+        bool settled;
+        uint8 status;
+        uint256 releaseAt;
+        uint256 paidAmount;
+        bool streaming;
+        uint256 streamStart;
+        uint256 streamedAmount;
+        uint256 streamWithdrawn;
+        uint256 claimableBalance;
+        uint256 attestedIl;
+        uint256 attestedLpValue;
+        uint256 attestedHoldingValue;
+        uint256 attestedPayout;
+        uint256 attestedAt;
+        uint256 attestedBlock;
+        bytes32 inputsHash;
+        bytes32 adjustmentReason;
+        bool claimantBlacklisted;
+        uint256 epochRecovered;
+    }
+
+    /// Consecutive claims starting at `firstClaimId`
+    struct ClaimsPage {
+        uint8 kind;
+        uint256 firstClaimId;
+        ClaimRecord[] claims;
+    }
+}
+
+/// Kind of an encoded page (read from its first word), or None if it is too short
+pub fn page_kind(data: &[u8]) -> Option<u8> {
+    if data.len() < 32 {
+        return None;
+    }
+    Some(data[31])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_sol_types::SolValue;
+    use stylus_sdk::alloy_primitives::{Address, FixedBytes, U256};
+
+    #[test]
+    fn test_claims_page_round_trip() {
+        let record = ClaimRecord {
+            claimant: Address::repeat_byte(0x42),
+            epoch: U256::from(3u8),
+            amount: U256::from(320u32),
+            settledAmount: U256::from(320u32),
+            settled: true,
+            status: 1,
+            releaseAt: U256::ZERO,
+            paidAmount: U256::from(320u32),
+            streaming: false,
+            streamStart: U256::ZERO,
+            streamedAmount: U256::ZERO,
+            streamWithdrawn: U256::ZERO,
+            claimableBalance: U256::from(320u32),
+            attestedIl: U256::from(5u8),
+            attestedLpValue: U256::from(2000u32),
+            attestedHoldingValue: U256::from(4000u32),
+            attestedPayout: U256::from(320u32),
+            attestedAt: U256::from(1_000u32),
+            attestedBlock: U256::from(7u8),
+            inputsHash: FixedBytes::repeat_byte(0xab),
+            adjustmentReason: FixedBytes::ZERO,
+            claimantBlacklisted: false,
+            epochRecovered: U256::from(40u8),
+        };
+        let page = ClaimsPage { kind: PAGE_CLAIMS, firstClaimId: U256::from(16u8), claims: vec![record] };
+
+        let encoded = page.abi_encode_params();
+        assert_eq!(page_kind(&encoded), Some(PAGE_CLAIMS));
+
+        let decoded = ClaimsPage::abi_decode_params(&encoded, true).unwrap();
+        assert_eq!(decoded.firstClaimId, U256::from(16u8));
+        assert_eq!(decoded.claims.len(), 1);
+        assert_eq!(decoded.claims[0].claimant, Address::repeat_byte(0x42));
+        assert_eq!(decoded.claims[0].claimableBalance, U256::from(320u32));
+        assert_eq!(decoded.claims[0].inputsHash, FixedBytes::repeat_byte(0xab));
+        assert_eq!(decoded.claims[0].epochRecovered, U256::from(40u8));
+
+        assert_eq!(page_kind(&[0u8; 8]), None);
+    }
+}
@@ -0,0 +1,244 @@
+// mocks.rs
+// Deterministic stand-ins for the external contracts in `interfaces`, backed by TestVM call mocks.
+// Each helper registers the ABI-encoded response the real contract would return.
+use alloy_sol_types::SolCall;
+use stylus_sdk::alloy_primitives::{Address, FixedBytes, Signed, Uint, I256, U256};
+use stylus_sdk::testing::TestVM;
+
+mod abi {
+    alloy_sol_types::sol! {
+        function decimals() external view returns (uint8);
+        function latestRoundData() external view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound);
+        function totalSupply() external view returns (uint256);
+        function balanceOf(address account) external view returns (uint256);
+        function transfer(address to, uint256 amount) external returns (bool);
+        function transferFrom(address from, address to, uint256 amount) external returns (bool);
+        function getReserves() external view returns (uint112 reserve0, uint112 reserve1, uint32 blockTimestampLast);
+        function price0CumulativeLast() external view returns (uint256);
+        function price1CumulativeLast() external view returns (uint256);
+        function recover(uint256 lossAmount) external returns (uint256);
+        function onPositionRegistered(address holder, address pool, uint256 lpAmount) external;
+        function proxiableUUID() external view returns (bytes32);
+        function stakedBalance(address account) external view returns (uint256);
+    }
+}
+
+mod v3_abi {
+    alloy_sol_types::sol! {
+        function observe(uint32[] secondsAgos) external view returns (int56[] tickCumulatives, uint160[] secondsPerLiquidityCumulativeX128s);
+    }
+}
+
+mod camelot_abi {
+    alloy_sol_types::sol! {
+        function getReserves() external view returns (uint112 reserve0, uint112 reserve1, uint16 token0FeePercent, uint16 token1FeePercent);
+        function stableSwap() external view returns (bool);
+    }
+}
+
+/// Mock Chainlink aggregator: `decimals()`
+pub fn mock_feed_decimals(vm: &TestVM, feed: Address, decimals: u8) {
+    vm.mock_static_call(
+        feed,
+        abi::decimalsCall {}.abi_encode(),
+        Ok(abi::decimalsCall::abi_encode_returns(&(decimals,))),
+    );
+}
+
+/// Mock Chainlink aggregator: `latestRoundData()` answering `answer` in `round_id`
+pub fn mock_feed_round(vm: &TestVM, feed: Address, round_id: u64, answer: I256, updated_at: U256) {
+    let round = Uint::<80, 2>::from(round_id);
+    vm.mock_static_call(
+        feed,
+        abi::latestRoundDataCall {}.abi_encode(),
+        Ok(abi::latestRoundDataCall::abi_encode_returns(&(
+            round, answer, updated_at, updated_at, round,
+        ))),
+    );
+}
+
+/// Mock Chainlink aggregator that reverts on `latestRoundData()`
+pub fn mock_feed_revert(vm: &TestVM, feed: Address) {
+    vm.mock_static_call(feed, abi::latestRoundDataCall {}.abi_encode(), Err(Vec::new()));
+}
+
+/// Mock ERC-20: `totalSupply()`
+pub fn mock_total_supply(vm: &TestVM, token: Address, supply: U256) {
+    vm.mock_static_call(
+        token,
+        abi::totalSupplyCall {}.abi_encode(),
+        Ok(abi::totalSupplyCall::abi_encode_returns(&(supply,))),
+    );
+}
+
+/// Mock staking registry: `stakedBalance(account)`
+pub fn mock_staked_balance(vm: &TestVM, registry: Address, account: Address, staked: U256) {
+    vm.mock_static_call(
+        registry,
+        abi::stakedBalanceCall { account }.abi_encode(),
+        Ok(abi::stakedBalanceCall::abi_encode_returns(&(staked,))),
+    );
+}
+
+/// Mock ERC-20: `balanceOf(account)`
+pub fn mock_balance_of(vm: &TestVM, token: Address, account: Address, balance: U256) {
+    vm.mock_static_call(
+        token,
+        abi::balanceOfCall { account }.abi_encode(),
+        Ok(abi::balanceOfCall::abi_encode_returns(&(balance,))),
+    );
+}
+
+/// Mock ERC-20: successful `transfer(to, amount)`
+pub fn mock_transfer(vm: &TestVM, token: Address, to: Address, amount: U256) {
+    vm.mock_call(
+        token,
+        abi::transferCall { to, amount }.abi_encode(),
+        U256::ZERO,
+        Ok(abi::transferCall::abi_encode_returns(&(true,))),
+    );
+}
+
+/// Mock ERC-20: successful `transferFrom(from, to, amount)`
+pub fn mock_transfer_from(vm: &TestVM, token: Address, from: Address, to: Address, amount: U256) {
+    vm.mock_call(
+        token,
+        abi::transferFromCall { from, to, amount }.abi_encode(),
+        U256::ZERO,
+        Ok(abi::transferFromCall::abi_encode_returns(&(true,))),
+    );
+}
+
+/// Mock Uniswap V2 pair: `getReserves()`
+pub fn mock_reserves(vm: &TestVM, pair: Address, reserve0: u128, reserve1: u128, timestamp: u32) {
+    vm.mock_static_call(
+        pair,
+        abi::getReservesCall {}.abi_encode(),
+        Ok(abi::getReservesCall::abi_encode_returns(&(
+            Uint::<112, 2>::from(reserve0),
+            Uint::<112, 2>::from(reserve1),
+            timestamp,
+        ))),
+    );
+}
+
+/// Mock Uniswap V2 pair: `price0CumulativeLast()` and `price1CumulativeLast()`
+pub fn mock_cumulative_prices(vm: &TestVM, pair: Address, price0_cumulative: U256, price1_cumulative: U256) {
+    vm.mock_static_call(
+        pair,
+        abi::price0CumulativeLastCall {}.abi_encode(),
+        Ok(abi::price0CumulativeLastCall::abi_encode_returns(&(price0_cumulative,))),
+    );
+    vm.mock_static_call(
+        pair,
+        abi::price1CumulativeLastCall {}.abi_encode(),
+        Ok(abi::price1CumulativeLastCall::abi_encode_returns(&(price1_cumulative,))),
+    );
+}
+
+/// Mock Uniswap V3 pool: `observe(seconds_agos)` answering one tick cumulative per entry
+pub fn mock_v3_observe(vm: &TestVM, pool: Address, seconds_agos: Vec<u32>, tick_cumulatives: Vec<i64>) {
+    let ticks = tick_cumulatives.into_iter().map(|tick| Signed::<56, 1>::try_from(tick).unwrap()).collect::<Vec<_>>();
+    let liquidity = vec![Uint::<160, 3>::ZERO; ticks.len()];
+    vm.mock_static_call(
+        pool,
+        v3_abi::observeCall { secondsAgos: seconds_agos }.abi_encode(),
+        Ok(v3_abi::observeCall::abi_encode_returns(&(ticks, liquidity))),
+    );
+}
+
+/// Mock Camelot pair: `getReserves()` (with 0.3% fees both ways) and `stableSwap()`
+pub fn mock_camelot_pair(vm: &TestVM, pair: Address, reserve0: u128, reserve1: u128, stable: bool) {
+    vm.mock_static_call(
+        pair,
+        camelot_abi::getReservesCall {}.abi_encode(),
+        Ok(camelot_abi::getReservesCall::abi_encode_returns(&(
+            Uint::<112, 2>::from(reserve0),
+            Uint::<112, 2>::from(reserve1),
+            300u16,
+            300u16,
+        ))),
+    );
+    vm.mock_static_call(
+        pair,
+        camelot_abi::stableSwapCall {}.abi_encode(),
+        Ok(camelot_abi::stableSwapCall::abi_encode_returns(&(stable,))),
+    );
+}
+
+/// Mock reinsurer: `recover(loss_amount)` paying back `recovered`
+pub fn mock_recover(vm: &TestVM, reinsurer: Address, loss_amount: U256, recovered: U256) {
+    vm.mock_call(
+        reinsurer,
+        abi::recoverCall { lossAmount: loss_amount }.abi_encode(),
+        U256::ZERO,
+        Ok(abi::recoverCall::abi_encode_returns(&(recovered,))),
+    );
+}
+
+/// Mock ecrecover precompile: `signature` over `digest` recovers to `signer`
+pub fn mock_ecrecover(vm: &TestVM, digest: FixedBytes<32>, signature: &[u8], signer: Address) {
+    let input = crate::crosschain::ecrecover_input(digest, signature).expect("malformed signature");
+    vm.mock_static_call(crate::crosschain::ECRECOVER, input, Ok(signer.into_word().to_vec()));
+}
+
+/// Mock UUPS implementation: `proxiableUUID()`
+pub fn mock_proxiable_uuid(vm: &TestVM, implementation: Address, uuid: FixedBytes<32>) {
+    vm.mock_static_call(
+        implementation,
+        abi::proxiableUUIDCall {}.abi_encode(),
+        Ok(abi::proxiableUUIDCall::abi_encode_returns(&(uuid,))),
+    );
+}
+
+/// Mock hooks contract that reverts on `onPositionRegistered(holder, pool, lp_amount)`
+pub fn mock_position_hook_revert(vm: &TestVM, hooks: Address, holder: Address, pool: Address, lp_amount: U256) {
+    vm.mock_call(
+        hooks,
+        abi::onPositionRegisteredCall { holder, pool, lpAmount: lp_amount }.abi_encode(),
+        U256::ZERO,
+        Err(Vec::new()),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interfaces::{IAggregatorV3, IERC20, IUniswapV2Pair};
+    use stylus_sdk::call::Call;
+
+    #[test]
+    fn test_mocks_answer_interface_calls() {
+        let vm = TestVM::default();
+        let token = Address::repeat_byte(0x01);
+        let feed = Address::repeat_byte(0x02);
+        let pair = Address::repeat_byte(0x03);
+        let holder = Address::repeat_byte(0x11);
+
+        mock_balance_of(&vm, token, holder, U256::from(42u8));
+        mock_total_supply(&vm, token, U256::from(1_000u32));
+        mock_feed_round(&vm, feed, 7, I256::try_from(2000i64).unwrap(), U256::from(1_000u32));
+        mock_reserves(&vm, pair, 500, 1_000_000, 1_000);
+
+        let erc20 = IERC20::new(token);
+        assert_eq!(erc20.balance_of(&vm, Call::new(), holder).unwrap(), U256::from(42u8));
+        assert_eq!(erc20.total_supply(&vm, Call::new()).unwrap(), U256::from(1_000u32));
+
+        let round = IAggregatorV3::new(feed).latest_round_data(&vm, Call::new()).unwrap();
+        assert_eq!(round.1, I256::try_from(2000i64).unwrap());
+        assert_eq!(round.3, U256::from(1_000u32));
+
+        let reserves = IUniswapV2Pair::new(pair).get_reserves(&vm, Call::new()).unwrap();
+        assert_eq!(reserves.0, Uint::<112, 2>::from(500u32));
+        assert_eq!(reserves.1, Uint::<112, 2>::from(1_000_000u32));
+    }
+
+    #[test]
+    fn test_reverting_feed() {
+        let vm = TestVM::default();
+        let feed = Address::repeat_byte(0x02);
+        mock_feed_revert(&vm, feed);
+
+        assert!(IAggregatorV3::new(feed).latest_round_data(&vm, Call::new()).is_err());
+    }
+}
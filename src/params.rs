@@ -0,0 +1,118 @@
+// params.rs
+// Validation and scaling of basis-point parameters. Every entry point that sets a coverage band
+// (initialize, update_policy, pool overrides, governance proposals, state import) goes through
+// `validate_policy`, so they all accept exactly the same bands.
+use crate::util::{mul_div_u256, SCALE};
+use stylus_sdk::alloy_primitives::U256;
+
+/// Basis points denominator (100% = 10000 bps)
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+/// A coverage band: IL between threshold and cap is paid at the payout ratio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PolicyParams {
+    pub threshold_bps: U256,
+    pub upper_cap_bps: U256,
+    pub payout_ratio_bps: U256,
+}
+
+/// Why a set of parameters was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamError {
+    /// The threshold is not strictly below the cap
+    InvalidThreshold,
+    /// The cap is above 100%
+    CapTooHigh,
+    /// A ratio is above 100%
+    RatioTooHigh,
+}
+
+impl PolicyParams {
+    pub fn new(threshold_bps: U256, upper_cap_bps: U256, payout_ratio_bps: U256) -> Self {
+        PolicyParams { threshold_bps, upper_cap_bps, payout_ratio_bps }
+    }
+}
+
+/// Whether `value` is a valid basis-point fraction, i.e. at most 100%
+pub fn is_bps(value: U256) -> bool {
+    value <= U256::from(BPS_DENOMINATOR)
+}
+
+/// Check that a ratio is at most 100%
+pub fn check_ratio(ratio_bps: U256) -> Result<(), ParamError> {
+    if !is_bps(ratio_bps) {
+        return Err(ParamError::RatioTooHigh);
+    }
+    Ok(())
+}
+
+/// Check that policy parameters form a valid coverage band: threshold < cap <= 100%, ratio <= 100%
+pub fn validate_policy(params: &PolicyParams) -> Result<(), ParamError> {
+    if params.threshold_bps >= params.upper_cap_bps {
+        return Err(ParamError::InvalidThreshold);
+    }
+    if !is_bps(params.upper_cap_bps) {
+        return Err(ParamError::CapTooHigh);
+    }
+    check_ratio(params.payout_ratio_bps)
+}
+
+/// Convert basis points to a fraction scaled by 1e18 (1000 bps = 0.1 * 1e18)
+pub fn bps_to_wad(bps: U256) -> U256 {
+    mul_div_u256(bps, U256::from(SCALE), U256::from(BPS_DENOMINATOR))
+}
+
+/// Convert a fraction scaled by 1e18 to basis points, rounding down
+pub fn wad_to_bps(wad: U256) -> U256 {
+    mul_div_u256(wad, U256::from(BPS_DENOMINATOR), U256::from(SCALE))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn band(threshold: u32, cap: u32, ratio: u32) -> PolicyParams {
+        PolicyParams::new(U256::from(threshold), U256::from(cap), U256::from(ratio))
+    }
+
+    #[test]
+    fn test_validate_policy() {
+        // Valid bands, including the extremes
+        assert_eq!(validate_policy(&band(1000, 2000, 8000)), Ok(()));
+        assert_eq!(validate_policy(&band(0, 1, 0)), Ok(()));
+        assert_eq!(validate_policy(&band(9999, 10_000, 10_000)), Ok(()));
+
+        // Band ordering: the threshold must be strictly below the cap
+        assert_eq!(validate_policy(&band(2000, 2000, 8000)), Err(ParamError::InvalidThreshold));
+        assert_eq!(validate_policy(&band(3000, 2000, 8000)), Err(ParamError::InvalidThreshold));
+        assert_eq!(validate_policy(&band(0, 0, 0)), Err(ParamError::InvalidThreshold));
+
+        // Ranges
+        assert_eq!(validate_policy(&band(1000, 10_001, 8000)), Err(ParamError::CapTooHigh));
+        assert_eq!(validate_policy(&band(1000, 2000, 10_001)), Err(ParamError::RatioTooHigh));
+        let huge = PolicyParams::new(U256::ZERO, U256::MAX, U256::MAX);
+        assert_eq!(validate_policy(&huge), Err(ParamError::CapTooHigh));
+
+        // Ordering is checked before ranges
+        assert_eq!(validate_policy(&band(20_000, 10_001, 10_001)), Err(ParamError::InvalidThreshold));
+        assert_eq!(validate_policy(&band(1000, 10_001, 10_001)), Err(ParamError::CapTooHigh));
+    }
+
+    #[test]
+    fn test_bps_ranges_and_scaling() {
+        assert!(is_bps(U256::ZERO) && is_bps(U256::from(BPS_DENOMINATOR)));
+        assert!(!is_bps(U256::from(BPS_DENOMINATOR + 1)));
+        assert_eq!(check_ratio(U256::from(10_000u32)), Ok(()));
+        assert_eq!(check_ratio(U256::from(10_001u32)), Err(ParamError::RatioTooHigh));
+
+        assert_eq!(bps_to_wad(U256::from(1000u32)), U256::from(SCALE / 10));
+        assert_eq!(bps_to_wad(U256::from(BPS_DENOMINATOR)), U256::from(SCALE));
+        assert_eq!(bps_to_wad(U256::from(1u8)), U256::from(SCALE / 10_000));
+        assert_eq!(wad_to_bps(U256::from(SCALE / 2)), U256::from(5000u32));
+        // Below one bps rounds down
+        assert_eq!(wad_to_bps(U256::from(SCALE / 10_000 - 1)), U256::ZERO);
+        for bps in [0u32, 1, 1000, 2500, 9999, 10_000] {
+            assert_eq!(wad_to_bps(bps_to_wad(U256::from(bps))), U256::from(bps));
+        }
+    }
+}
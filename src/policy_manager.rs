@@ -1,8 +1,12 @@
 // policy_manager.rs (claim using constants)
+#[cfg(feature = "demo")]
 use crate::lp_valuator::compute_values_from_constants;
+#[cfg(feature = "demo")]
 use crate::mul_div;
+#[cfg(feature = "demo")]
 use crate::util::SCALE;
 
+#[cfg(feature = "demo")]
 pub fn claim_demo() -> (u128 /*payout_usd*/, u128 /*il_frac*/) {
     let (_lp_value, holding_value, il_frac) = compute_values_from_constants();
 
@@ -0,0 +1,175 @@
+// pricing.rs
+// Volatility-aware premium math: the IL of a constant-product position behaves like a short
+// straddle, so the expected covered loss grows with the variance of the pair's price ratio.
+use crate::params::{bps_to_wad, BPS_DENOMINATOR};
+use crate::util::{exp_wad, ln_wad, mul_div_u256, SCALE};
+use alloc::vec::Vec;
+use stylus_sdk::alloy_primitives::U256;
+
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+/// |Z| for a standard normal Z at the midpoints of 8 equal-probability buckets (scaled by SCALE)
+const HALF_NORMAL_QUANTILES: [u128; 8] = [
+    78_412_000_000_000_000,
+    237_202_000_000_000_000,
+    402_250_000_000_000_000,
+    579_132_000_000_000_000,
+    776_422_000_000_000_000,
+    1_009_990_000_000_000_000,
+    1_318_011_000_000_000_000,
+    1_862_732_000_000_000_000,
+];
+
+/// Expected banded loss, as a fraction of the holding value (scaled by SCALE), over `term_seconds`
+/// for a pair whose log price ratio has annualized volatility `volatility` (scaled by SCALE).
+///
+/// The log move x = volatility * sqrt(term) * Z is sampled at the bucket midpoints above and
+/// valued with the exact constant-product IL, 1 - sech(x / 2).
+pub fn expected_band_loss(volatility: U256, term_seconds: u64, threshold_bps: U256, upper_cap_bps: U256) -> U256 {
+    let scale = U256::from(SCALE);
+    let threshold = bps_to_wad(threshold_bps);
+    let upper = bps_to_wad(upper_cap_bps);
+    if upper <= threshold {
+        return U256::ZERO;
+    }
+
+    // Standard deviation of the log move over the term (scaled by SCALE)
+    let annual_variance = mul_div_u256(volatility, volatility, scale);
+    let variance = mul_div_u256(annual_variance, U256::from(term_seconds), U256::from(SECONDS_PER_YEAR));
+    let deviation = variance.saturating_mul(scale).root(2);
+
+    let mut total = U256::ZERO;
+    for z in HALF_NORMAL_QUANTILES {
+        let log_move = mul_div_u256(deviation, U256::from(z), scale);
+        let covered = il_of_log_move(log_move).min(upper).saturating_sub(threshold);
+        total += covered;
+    }
+    total / U256::from(HALF_NORMAL_QUANTILES.len())
+}
+
+/// Constant-product IL (scaled by SCALE) for a log price-ratio move `x` (scaled by SCALE):
+/// 1 - 2q / (1 + q^2) with q = e^(-x/2), i.e. 1 - sech(x / 2)
+pub fn il_of_log_move(x: U256) -> U256 {
+    let scale = U256::from(SCALE);
+    // IL is symmetric in x; taking the negative half keeps q <= 1 so nothing overflows
+    let half = i128::try_from(x / U256::from(2u8)).unwrap_or(i128::MAX);
+    let q = exp_wad(-half);
+    let denominator = scale + mul_div_u256(q, q, scale);
+    let ratio = mul_div_u256(q * U256::from(2u8), scale, denominator);
+    scale.saturating_sub(ratio)
+}
+
+/// Premium (same units as `holding_value`) for covering a position over `term_seconds`:
+/// expected banded loss times the payout ratio.
+pub fn quote_premium(
+    holding_value: U256,
+    volatility: U256,
+    term_seconds: u64,
+    threshold_bps: U256,
+    upper_cap_bps: U256,
+    payout_ratio_bps: U256,
+) -> U256 {
+    let loss_frac = expected_band_loss(volatility, term_seconds, threshold_bps, upper_cap_bps);
+    let expected_loss = mul_div_u256(holding_value, loss_frac, U256::from(SCALE));
+    mul_div_u256(expected_loss, payout_ratio_bps, U256::from(BPS_DENOMINATOR))
+}
+
+/// Annualized realized volatility (scaled by SCALE) of a (timestamp, price) series in time order.
+///
+/// Uses squared log returns with a zero-mean assumption, weighted by elapsed time so
+/// irregularly spaced samples are handled. Returns 0 with fewer than two usable samples.
+pub fn realized_volatility(samples: &[(u64, U256)]) -> U256 {
+    let scale = U256::from(SCALE);
+    let mut sum_squared_returns = U256::ZERO;
+    let mut elapsed = 0u64;
+
+    let usable: Vec<&(u64, U256)> = samples.iter().filter(|(_, price)| *price > U256::ZERO).collect();
+    for pair in usable.windows(2) {
+        let (t0, p0) = *pair[0];
+        let (t1, p1) = *pair[1];
+        if t1 <= t0 {
+            continue;
+        }
+        let log_return = ln_wad(mul_div_u256(p1, scale, p0)).unwrap_or(0);
+        let ret = U256::from(log_return.unsigned_abs());
+        sum_squared_returns = sum_squared_returns.saturating_add(mul_div_u256(ret, ret, scale));
+        elapsed += t1 - t0;
+    }
+    if elapsed == 0 {
+        return U256::ZERO;
+    }
+
+    // Annual variance scaled by SCALE, then sqrt(variance * SCALE) keeps the result scaled by SCALE
+    let annual_variance = mul_div_u256(sum_squared_returns, U256::from(SECONDS_PER_YEAR), U256::from(elapsed));
+    annual_variance.saturating_mul(scale).root(2)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn scaled(value: u128) -> U256 {
+        U256::from(value) * U256::from(SCALE)
+    }
+
+    #[test]
+    fn test_no_volatility_no_premium() {
+        let premium = quote_premium(scaled(4000), U256::ZERO, SECONDS_PER_YEAR, U256::from(1000u32), U256::from(2000u32), U256::from(8000u32));
+        assert_eq!(premium, U256::ZERO);
+    }
+
+    #[test]
+    fn test_expected_loss_at_full_volatility() {
+        // sigma = 100%, one year, band 0-100%: mean(1 - sech(z / 2)) over the buckets ~= 0.0944886
+        let loss = expected_band_loss(U256::from(SCALE), SECONDS_PER_YEAR, U256::ZERO, U256::from(10_000u32));
+        let expected = U256::from(94_488_611_505_281_800u128);
+        let tolerance = U256::from(SCALE / 10_000);
+        assert!(loss.abs_diff(expected) < tolerance, "loss = {loss}");
+    }
+
+    #[test]
+    fn test_il_of_log_move() {
+        assert_eq!(il_of_log_move(U256::ZERO), U256::ZERO);
+        // Price ratio x4 (ln 4 = 1.386294): IL = 1 - 2 * 2 / 5 = 20%
+        let il = il_of_log_move(U256::from(1_386_294_361_119_890_618u128));
+        assert!(il.abs_diff(U256::from(SCALE / 5)) < U256::from(1_000u32), "il = {il}");
+        // Extreme moves approach 100% without overflowing
+        let extreme = il_of_log_move(U256::from(SCALE) * U256::from(500u32));
+        assert!(extreme > U256::from(SCALE) - U256::from(1_000u32) && extreme <= U256::from(SCALE));
+    }
+
+    #[test]
+    fn test_volatile_pairs_pay_more() {
+        let quote = |volatility: U256, term: u64| {
+            quote_premium(scaled(4000), volatility, term, U256::from(1000u32), U256::from(2000u32), U256::from(8000u32))
+        };
+        let stable = quote(U256::from(SCALE / 20), SECONDS_PER_YEAR); // 5%
+        let major = quote(U256::from(SCALE * 8 / 10), SECONDS_PER_YEAR); // 80%
+        let meme = quote(U256::from(SCALE * 2), SECONDS_PER_YEAR); // 200%
+
+        assert_eq!(stable, U256::ZERO); // never reaches the 10% threshold
+        assert!(major > stable);
+        assert!(meme > major);
+        // Longer cover costs more
+        assert!(quote(U256::from(SCALE * 8 / 10), SECONDS_PER_YEAR / 4) < major);
+        // Capped: never more than the full band times the payout ratio
+        assert!(meme <= scaled(4000) / U256::from(10u8) * U256::from(8u8) / U256::from(10u8));
+    }
+
+    #[test]
+    fn test_realized_volatility() {
+        // Flat prices: no volatility
+        let flat = [(0, scaled(2000)), (3_600, scaled(2000)), (7_200, scaled(2000))];
+        assert_eq!(realized_volatility(&flat), U256::ZERO);
+
+        // A 1% move every day: daily log variance ~1e-4, annualized ~= sqrt(365) * ln(1.01) ~= 19.01%
+        let day = 86_400u64;
+        let samples = [(0, scaled(100)), (day, scaled(101)), (2 * day, scaled(100) * U256::from(10201u32) / U256::from(10_000u32))];
+        let volatility = realized_volatility(&samples);
+        let expected = U256::from(190_100_804_027_602_000u128);
+        assert!(volatility.abs_diff(expected) < U256::from(SCALE / 1000), "volatility = {volatility}");
+
+        // Too few samples
+        assert_eq!(realized_volatility(&[(0, scaled(1))]), U256::ZERO);
+    }
+}
@@ -0,0 +1,118 @@
+// state_view.rs
+// ABI layout of the `get_full_state` blob: every scalar storage field in one call, grouped by
+// subsystem so monitoring bots can diff it and archival tools can snapshot it. Mappings and
+// arrays are summarized by their counters; read them through their own getters.
+use alloy_sol_types::sol;
+
+/// Layout version of `FullState`, bumped whenever a field is added, removed or reordered
+pub const FULL_STATE_VERSION: u64 = 14;
+
+sol! {
+    /// Global policy and the tracked pool
+    struct PolicyState {
+        uint256 thresholdBps;
+        uint256 upperCapBps;
+        uint256 payoutRatioBps;
+        uint8 benchmark;
+        address pool;
+        uint8 poolStatus;
+    }
+
+    /// Pool state, prices and oracle configuration
+    struct MarketState {
+        uint256 reserveA;
+        uint256 reserveB;
+        uint256 lpTotalSupply;
+        uint256 kToleranceBps;
+        uint256 lpMintedTotal;
+        uint256 lpBurnedTotal;
+        bool liveLpSupply;
+        uint256 priceA;
+        uint256 priceB;
+        address quoteAsset;
+        uint256 quotePriceUsd;
+        uint256 maxPriceAge;
+        address sequencerUptimeFeed;
+        uint256 sequencerGracePeriod;
+        uint256 volatility;
+        address volatilityOracle;
+        uint256 twapWindow;
+        bool twapAnchoredEntry;
+        bool storedIlSnapshots;
+        uint256 storedLpUnitValue;
+        uint256 storedValueAt;
+        uint256 marketSnapshotCount;
+        address stakingRegistry;
+        uint256 discountTierCount;
+    }
+
+    /// Covered position and its LP escrow
+    struct PositionState {
+        uint256 lpAmount;
+        uint256 originalA;
+        uint256 originalB;
+        address positionHolder;
+        bytes32 policyId;
+        bool lpEscrowRequired;
+        address escrowHolder;
+        uint256 escrowedLp;
+        uint256 claimedIl;
+        uint256 lastClaimAt;
+        uint256 registeredAt;
+    }
+
+    /// Claims queue, review, throttling and streaming configuration
+    struct ClaimsState {
+        uint256 epochDuration;
+        uint256 epochGenesis;
+        uint256 epochPayoutBudget;
+        uint256 claimCount;
+        uint256 largeClaimThreshold;
+        uint256 challengePeriod;
+        address challenger;
+        address arbiter;
+        address claimsAdjuster;
+        uint256 maxAdjustmentBps;
+        uint256 approverCount;
+        uint256 requiredApprovals;
+        uint256 rateLimitWindow;
+        uint256 maxPayoutPerWindow;
+        bool commitRevealRequired;
+        uint256 revealDelay;
+        uint256 revealWindow;
+        uint256 streamThreshold;
+        uint256 streamDuration;
+    }
+
+    /// Ownership, integrations, reinsurance and governance
+    struct AdminState {
+        address owner;
+        address pendingOwner;
+        bool initialized;
+        uint256 shutdownAt;
+        uint256 settlementWindow;
+        address hooks;
+        uint256 hookGasLimit;
+        address lzEndpoint;
+        uint256 relayerCount;
+        uint256 relayerQuorum;
+        uint256 remoteStateRound;
+        address reinsurer;
+        uint256 reinsuranceRetention;
+        address governanceToken;
+        uint256 votingPeriod;
+        uint256 proposalQuorum;
+        uint256 proposalCount;
+    }
+
+    /// Versioned snapshot of the whole contract
+    struct FullState {
+        uint256 formatVersion;
+        uint256 storageVersion;
+        PolicyState policy;
+        MarketState market;
+        PositionState position;
+        ClaimsState claims;
+        AdminState admin;
+    }
+}
@@ -1,5 +1,5 @@
 // utils.rs
-use stylus_sdk::alloy_primitives::U256;
+use stylus_sdk::alloy_primitives::{U256, U512};
 
 pub const SCALE: u128 = crate::constant::SCALE;
 
@@ -28,7 +28,172 @@ pub fn mul_div(a: u128, b: u128, denom: u128) -> u128 {
     }
 }
 
-// compute user share: lp_amount / total_supply, scaled by SCALE
+// compute user share: lp_amount / total_supply, scaled by SCALE and capped at 100%
 pub fn user_share_scaled(lp_amount: u128, total_supply: u128) -> u128 {
-    mul_div(lp_amount, SCALE, total_supply)
+    mul_div(lp_amount, SCALE, total_supply).min(SCALE)
+}
+
+// Full-precision (a * b) / denom on U256 values, widening to U512 so the product cannot overflow
+pub fn mul_div_u256(a: U256, b: U256, denom: U256) -> U256 {
+    if denom == U256::ZERO {
+        return U256::ZERO; // avoid division by zero
+    }
+
+    let result = (U512::from(a) * U512::from(b)) / U512::from(denom);
+
+    // Saturate instead of panicking if the quotient does not fit back into U256
+    U256::saturating_from(result)
+}
+
+// Natural log of 2, scaled by 1e18
+const LN2_WAD: i128 = 693_147_180_559_945_309;
+// Inputs above this overflow U256 in exp_wad; below EXP_WAD_MIN the result rounds to 0
+const EXP_WAD_MAX: i128 = 135_305_999_368_893_231_588;
+const EXP_WAD_MIN: i128 = -42_139_678_854_452_767_551;
+
+// e^x for x scaled by 1e18, result scaled by 1e18.
+// Range-reduces x = k*ln2 + r with |r| <= ln2/2 and sums the Taylor series of e^r until the
+// terms vanish, so the relative error stays below 1e-16. Saturates to U256::MAX above
+// EXP_WAD_MAX and returns 0 below EXP_WAD_MIN.
+pub fn exp_wad(x: i128) -> U256 {
+    if x > EXP_WAD_MAX {
+        return U256::MAX;
+    }
+    if x < EXP_WAD_MIN {
+        return U256::ZERO;
+    }
+
+    let wad = SCALE as i128;
+    let k = if x >= 0 { (x + LN2_WAD / 2) / LN2_WAD } else { (x - LN2_WAD / 2) / LN2_WAD };
+    let r = x - k * LN2_WAD;
+
+    let mut sum = wad;
+    let mut term = wad;
+    let mut n = 1i128;
+    while term != 0 {
+        term = term * r / (wad * n);
+        sum += term;
+        n += 1;
+    }
+
+    let e_r = U256::from(sum as u128);
+    if k >= 0 {
+        e_r << (k as usize)
+    } else {
+        e_r >> ((-k) as usize)
+    }
+}
+
+// ln(x) for x scaled by 1e18, result scaled by 1e18; None for x = 0.
+// Normalizes x = m * 2^k with m in [1, 2) and evaluates ln(m) = 2 * atanh((m - 1) / (m + 1))
+// by its series until the terms vanish; absolute error below 1e-17 across the U256 range.
+pub fn ln_wad(x: U256) -> Option<i128> {
+    if x == U256::ZERO {
+        return None;
+    }
+
+    let wad = SCALE as i128;
+    // Bring x into [2^59, 2^60), the bit length of 1e18, then into [1e18, 2e18)
+    let mut k = x.bit_len() as i128 - 60;
+    let normalized = if k >= 0 { x >> (k as usize) } else { x << ((-k) as usize) };
+    let mut m = normalized.to::<u128>() as i128;
+    if m < wad {
+        m *= 2;
+        k -= 1;
+    }
+
+    let s = (m - wad) * wad / (m + wad);
+    let s_squared = s * s / wad;
+    let mut sum = 0i128;
+    let mut term = s;
+    let mut n = 1i128;
+    while term != 0 {
+        sum += term / n;
+        term = term * s_squared / wad;
+        n += 2;
+    }
+
+    Some(k * LN2_WAD + 2 * sum)
+}
+
+// Most items a list view returns per call, and the page size when the caller passes limit 0
+pub const MAX_PAGE_SIZE: u64 = 50;
+
+// (cursor, limit) pagination over a list of `total` items: the index range [start, end) to serve
+// and the cursor of the next page (0 when done). The limit is clamped to 1..=MAX_PAGE_SIZE.
+// None if the cursor is past the end; cursor 0 is always valid, even for an empty list.
+pub fn page_range(cursor: U256, limit: U256, total: U256) -> Option<(U256, U256, U256)> {
+    if cursor != U256::ZERO && cursor >= total {
+        return None;
+    }
+    let max = U256::from(MAX_PAGE_SIZE);
+    let size = if limit == U256::ZERO || limit > max { max } else { limit };
+    let end = cursor.saturating_add(size).min(total);
+    let next = if end < total { end } else { U256::ZERO };
+    Some((cursor, end, next))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    const E_WAD: u128 = 2_718_281_828_459_045_235;
+
+    fn close(actual: U256, expected: U256, max_relative_error: U256) -> bool {
+        actual.abs_diff(expected) <= mul_div_u256(expected, max_relative_error, U256::from(SCALE)).max(U256::from(1u8))
+    }
+
+    #[test]
+    fn test_exp_wad_known_values() {
+        assert_eq!(exp_wad(0), U256::from(SCALE));
+        assert!(close(exp_wad(SCALE as i128), U256::from(E_WAD), U256::from(100u8)));
+        assert!(close(exp_wad(-(SCALE as i128)), U256::from(367_879_441_171_442_321u128), U256::from(100u8)));
+        // e^10 = 22026.465794806716517
+        assert!(close(exp_wad(10 * SCALE as i128), U256::from(22_026_465_794_806_716_516_957u128), U256::from(100u8)));
+        assert_eq!(exp_wad(EXP_WAD_MAX + 1), U256::MAX);
+        assert_eq!(exp_wad(EXP_WAD_MIN - 1), U256::ZERO);
+    }
+
+    #[test]
+    fn test_ln_wad_known_values() {
+        assert_eq!(ln_wad(U256::ZERO), None);
+        assert_eq!(ln_wad(U256::from(SCALE)), Some(0));
+        assert!((ln_wad(U256::from(E_WAD)).unwrap() - SCALE as i128).abs() <= 10);
+        assert!((ln_wad(U256::from(2 * SCALE)).unwrap() - LN2_WAD).abs() <= 10);
+        assert!((ln_wad(U256::from(SCALE / 2)).unwrap() + LN2_WAD).abs() <= 10);
+        // ln(1e-18) = -41.446531673892822312
+        assert!((ln_wad(U256::from(1u8)).unwrap() + 41_446_531_673_892_822_312).abs() <= 10);
+    }
+
+    #[test]
+    fn test_page_range() {
+        let n = |value: u64| U256::from(value);
+        assert_eq!(page_range(n(0), n(10), n(0)), Some((n(0), n(0), n(0))));
+        assert_eq!(page_range(n(0), n(10), n(25)), Some((n(0), n(10), n(10))));
+        assert_eq!(page_range(n(20), n(10), n(25)), Some((n(20), n(25), n(0))));
+        // Limit 0 and oversized limits serve a full page
+        assert_eq!(page_range(n(0), n(0), n(120)), Some((n(0), n(MAX_PAGE_SIZE), n(MAX_PAGE_SIZE))));
+        assert_eq!(page_range(n(100), U256::MAX, n(120)), Some((n(100), n(120), n(0))));
+        // Cursors past the end
+        assert_eq!(page_range(n(25), n(10), n(25)), None);
+        assert_eq!(page_range(n(1), n(10), n(0)), None);
+    }
+
+    proptest! {
+        #[test]
+        fn exp_inverts_ln(x in 1u128..=u128::MAX) {
+            let ln = ln_wad(U256::from(x)).unwrap();
+            let roundtrip = exp_wad(ln);
+            // 1e-15 relative, plus rounding for tiny inputs
+            let x = U256::from(x);
+            prop_assert!(roundtrip.abs_diff(x) <= mul_div_u256(x, U256::from(1_000u32), U256::from(SCALE)) + U256::from(2u8));
+        }
+
+        #[test]
+        fn ln_is_monotonic(a in 1u128..=u128::MAX, b in 1u128..=u128::MAX) {
+            let (low, high) = if a <= b { (a, b) } else { (b, a) };
+            prop_assert!(ln_wad(U256::from(low)).unwrap() <= ln_wad(U256::from(high)).unwrap());
+        }
+    }
 }
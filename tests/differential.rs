@@ -0,0 +1,225 @@
+//! Differential tests against the Solidity reference in `reference/ILReference.sol`.
+//!
+//! `reference` below mirrors the Solidity library line by line: wrapping `unchecked` arithmetic
+//! and `mulmod` in `mulDiv`, checked `+` and `-` elsewhere. A revert is `None`. Over random
+//! inputs, the contract's pure `calc_il` / `calc_payout` must return bit-identical results
+//! wherever the reference does not revert. Run with:
+//!
+//! ```bash
+//! cargo test --features types --test differential
+//! ```
+
+#![cfg(feature = "types")]
+
+use alloy_primitives::U256;
+use proptest::prelude::*;
+use stylus_hello_world::{ILInsurance, CONSTANT_SCALE as SCALE};
+
+/// Rust mirror of `ILReference.sol`
+mod reference {
+    use alloy_primitives::U256;
+
+    const SCALE: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+    const BPS_DENOMINATOR: U256 = U256::from_limbs([10_000, 0, 0, 0]);
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn calculate_il(
+        reserve_a: U256,
+        reserve_b: U256,
+        lp_total_supply: U256,
+        price_a: U256,
+        price_b: U256,
+        lp_amount: U256,
+        original_a: U256,
+        original_b: U256,
+    ) -> Option<U256> {
+        let lp = lp_value(reserve_a, reserve_b, lp_total_supply, price_a, price_b, lp_amount)?;
+        let holding = holding_value(original_a, original_b, price_a, price_b)?;
+        if holding.is_zero() || lp >= holding {
+            return Some(U256::ZERO);
+        }
+        mul_div(holding - lp, SCALE, holding)
+    }
+
+    pub fn calculate_payout(
+        il: U256,
+        holding: U256,
+        threshold_bps: U256,
+        upper_cap_bps: U256,
+        payout_ratio_bps: U256,
+    ) -> Option<U256> {
+        let threshold = mul_div(threshold_bps, SCALE, BPS_DENOMINATOR)?;
+        let upper_cap = mul_div(upper_cap_bps, SCALE, BPS_DENOMINATOR)?;
+        let capped = if il > upper_cap { upper_cap } else { il };
+        if capped <= threshold {
+            return Some(U256::ZERO);
+        }
+        let loss = mul_div(holding, capped - threshold, SCALE)?;
+        mul_div(loss, payout_ratio_bps, BPS_DENOMINATOR)
+    }
+
+    fn user_share(lp_amount: U256, lp_total_supply: U256) -> Option<U256> {
+        if lp_total_supply.is_zero() {
+            return Some(U256::ZERO);
+        }
+        let share = mul_div(lp_amount, SCALE, lp_total_supply)?;
+        Some(if share > SCALE { SCALE } else { share })
+    }
+
+    fn lp_value(
+        reserve_a: U256,
+        reserve_b: U256,
+        lp_total_supply: U256,
+        price_a: U256,
+        price_b: U256,
+        lp_amount: U256,
+    ) -> Option<U256> {
+        let share = user_share(lp_amount, lp_total_supply)?;
+        let value_a = mul_div(mul_div(reserve_a, share, SCALE)?, price_a, SCALE)?;
+        let value_b = mul_div(mul_div(reserve_b, share, SCALE)?, price_b, SCALE)?;
+        value_a.checked_add(value_b)
+    }
+
+    pub fn holding_value(original_a: U256, original_b: U256, price_a: U256, price_b: U256) -> Option<U256> {
+        mul_div(original_a, price_a, SCALE)?.checked_add(mul_div(original_b, price_b, SCALE)?)
+    }
+
+    fn bit(condition: bool) -> U256 {
+        if condition { U256::from(1u8) } else { U256::ZERO }
+    }
+
+    /// `mulDiv` as the EVM executes it: 256-bit wrapping words, `mulmod` and `div` opcodes
+    pub fn mul_div(x: U256, y: U256, denominator: U256) -> Option<U256> {
+        let mut prod0 = x.wrapping_mul(y);
+        let mm = x.mul_mod(y, U256::MAX);
+        let mut prod1 = mm.wrapping_sub(prod0).wrapping_sub(bit(mm < prod0));
+        if prod1.is_zero() {
+            return prod0.checked_div(denominator);
+        }
+        if denominator <= prod1 {
+            return None;
+        }
+
+        let remainder = x.mul_mod(y, denominator);
+        prod1 = prod1.wrapping_sub(bit(remainder > prod0));
+        prod0 = prod0.wrapping_sub(remainder);
+
+        let mut twos = denominator & U256::ZERO.wrapping_sub(denominator);
+        let denominator = denominator / twos;
+        prod0 /= twos;
+        twos = (U256::ZERO.wrapping_sub(twos) / twos).wrapping_add(U256::from(1u8));
+        prod0 |= prod1.wrapping_mul(twos);
+
+        let mut inverse = U256::from(3u8).wrapping_mul(denominator) ^ U256::from(2u8);
+        for _ in 0..6 {
+            inverse = inverse.wrapping_mul(U256::from(2u8).wrapping_sub(denominator.wrapping_mul(inverse)));
+        }
+        Some(prod0.wrapping_mul(inverse))
+    }
+}
+
+fn any_u256() -> impl Strategy<Value = U256> {
+    any::<[u8; 32]>().prop_map(U256::from_be_bytes)
+}
+
+/// Token-sized values: mostly realistic magnitudes, with zero and tiny amounts mixed in
+fn any_amount() -> impl Strategy<Value = U256> {
+    prop_oneof![
+        Just(U256::ZERO),
+        (1u128..1_000_000).prop_map(U256::from),
+        any::<u128>().prop_map(U256::from),
+        (1u128..1_000_000_000_000_000_000_000_000_000).prop_map(U256::from),
+    ]
+}
+
+fn any_fraction() -> impl Strategy<Value = U256> {
+    (0u128..=SCALE).prop_map(U256::from)
+}
+
+fn any_bps() -> impl Strategy<Value = U256> {
+    (0u32..=20_000).prop_map(U256::from)
+}
+
+#[test]
+fn test_reference_demo_position() {
+    let wad = |value: u128| U256::from(value * SCALE);
+    let il = reference::calculate_il(
+        wad(500), wad(1_000_000), wad(1_000_000), wad(2000), wad(1), wad(1000), wad(1), wad(2000),
+    );
+    assert_eq!(il, Some(U256::from(SCALE / 2)));
+    let holding = reference::holding_value(wad(1), wad(2000), wad(2000), wad(1)).unwrap();
+    let (threshold, cap, ratio) = (U256::from(1000u32), U256::from(2000u32), U256::from(8000u32));
+    assert_eq!(reference::calculate_payout(il.unwrap(), holding, threshold, cap, ratio), Some(wad(320)));
+
+    // The mirrored mulDiv is exact across the full 512-bit product and reverts past uint256
+    assert_eq!(reference::mul_div(U256::MAX, U256::MAX, U256::MAX), Some(U256::MAX));
+    assert_eq!(reference::mul_div(U256::MAX, U256::from(2u8), U256::from(2u8)), Some(U256::MAX));
+    assert_eq!(reference::mul_div(U256::MAX, U256::from(2u8), U256::from(1u8)), None);
+    assert_eq!(reference::mul_div(U256::from(1u8), U256::from(1u8), U256::ZERO), None);
+}
+
+proptest! {
+    #[test]
+    fn il_matches_reference(
+        reserve_a in any_amount(),
+        reserve_b in any_amount(),
+        lp_total_supply in any_amount(),
+        price_a in any_amount(),
+        price_b in any_amount(),
+        lp_amount in any_amount(),
+        original_a in any_amount(),
+        original_b in any_amount(),
+    ) {
+        let expected = reference::calculate_il(
+            reserve_a, reserve_b, lp_total_supply, price_a, price_b, lp_amount, original_a, original_b,
+        );
+        // Token-sized inputs never reach the reference's overflow reverts
+        let expected = expected.expect("reference reverted");
+        let il = ILInsurance::calc_il(
+            reserve_a, reserve_b, lp_total_supply, price_a, price_b, lp_amount, original_a, original_b,
+        );
+        prop_assert_eq!(il, expected);
+    }
+
+    #[test]
+    fn il_matches_reference_full_range(
+        reserve_a in any_u256(),
+        reserve_b in any_u256(),
+        lp_total_supply in any_u256(),
+        price_a in any_u256(),
+        price_b in any_u256(),
+        lp_amount in any_u256(),
+        original_a in any_u256(),
+        original_b in any_u256(),
+    ) {
+        // Where Solidity reverts the contract saturates instead; everywhere else the results agree
+        let expected = reference::calculate_il(
+            reserve_a, reserve_b, lp_total_supply, price_a, price_b, lp_amount, original_a, original_b,
+        );
+        let il = ILInsurance::calc_il(
+            reserve_a, reserve_b, lp_total_supply, price_a, price_b, lp_amount, original_a, original_b,
+        );
+        if let Some(expected) = expected {
+            prop_assert_eq!(il, expected);
+        }
+    }
+
+    #[test]
+    fn payout_matches_reference(
+        il in any_fraction(),
+        holding in prop_oneof![any_amount(), any_u256()],
+        threshold_bps in any_bps(),
+        upper_cap_bps in any_bps(),
+        payout_ratio_bps in any_bps(),
+    ) {
+        let expected = reference::calculate_payout(il, holding, threshold_bps, upper_cap_bps, payout_ratio_bps);
+        // A loss of at most `holding` scaled by a ratio of at most 200% can only overflow for
+        // holdings near the top of uint256
+        if let Some(expected) = expected {
+            let payout = ILInsurance::calc_payout(il, holding, threshold_bps, upper_cap_bps, payout_ratio_bps);
+            prop_assert_eq!(payout, expected);
+        } else {
+            prop_assert!(holding > U256::MAX / U256::from(2u8));
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! End-to-end tests against a local Nitro dev node.
+//!
+//! Deploys the compiled contract with `cargo stylus deploy`, then drives the full
+//! register → sync → claim flow through a JSON-RPC client so ABI routing and the
+//! storage layout are exercised exactly as on-chain. Requires a running
+//! nitro-devnode (https://github.com/OffchainLabs/nitro-devnode) and `cargo-stylus`:
+//!
+//! ```bash
+//! PRIV_KEY_PATH=<key file> cargo test --features e2e --test e2e -- --nocapture
+//! ```
+
+#![cfg(feature = "e2e")]
+
+use dotenv::dotenv;
+use ethers::{
+    middleware::SignerMiddleware,
+    prelude::abigen,
+    providers::{Http, Middleware, Provider},
+    signers::{LocalWallet, Signer},
+    types::{Address, U256},
+};
+use eyre::eyre;
+use std::io::{BufRead, BufReader};
+use std::process::Command;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Your private key file path.
+const PRIV_KEY_PATH: &str = "PRIV_KEY_PATH";
+
+/// Stylus RPC endpoint url (defaults to the nitro-devnode endpoint).
+const RPC_URL: &str = "RPC_URL";
+const DEFAULT_RPC_URL: &str = "http://localhost:8547";
+
+abigen!(
+    ILInsurance,
+    r#"[
+        function initialize(uint256 threshold_bps, uint256 upper_cap_bps, uint256 payout_ratio_bps) external
+        function getPolicy() external view returns (uint256, uint256, uint256)
+        function updatePoolState(uint256 reserve_a, uint256 reserve_b, uint256 total_supply) external
+        function updatePrices(uint256 price_a, uint256 price_b) external
+        function updateUserPosition(uint256 lp_amount, uint256 original_a, uint256 original_b) external
+        function calculateIl() external view returns (uint256)
+        function calculatePayout() external view returns (uint256)
+        function claim() external returns (uint256)
+        function setEpochDuration(uint256 duration) external
+        function getClaimCount() external view returns (uint256)
+        function getClaim(uint256 claim_id) external view returns (address, uint256, uint256, uint256, bool, uint8, uint256)
+    ]"#
+);
+
+#[tokio::test]
+async fn test_register_sync_claim_on_devnode() -> eyre::Result<()> {
+    dotenv().ok();
+    let priv_key_path =
+        std::env::var(PRIV_KEY_PATH).map_err(|_| eyre!("No {} env var set", PRIV_KEY_PATH))?;
+    let rpc_url = std::env::var(RPC_URL).unwrap_or_else(|_| DEFAULT_RPC_URL.to_string());
+
+    let address = deploy(&rpc_url, &priv_key_path)?;
+    println!("Deployed ILInsurance at {:?}", address);
+
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let privkey = read_secret_from_file(&priv_key_path)?;
+    let wallet = LocalWallet::from_str(&privkey)?;
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let client = Arc::new(SignerMiddleware::new(
+        provider,
+        wallet.clone().with_chain_id(chain_id),
+    ));
+    let contract = ILInsurance::new(address, client);
+    let scale = U256::exp10(18);
+
+    // Initialize: 10% threshold, 20% cap, 80% payout
+    contract
+        .initialize(U256::from(1000), U256::from(2000), U256::from(8000))
+        .send()
+        .await?
+        .await?;
+    let (threshold, cap, ratio) = contract.get_policy().call().await?;
+    assert_eq!((threshold, cap, ratio), (U256::from(1000), U256::from(2000), U256::from(8000)));
+
+    // Register the position: 1000 LP from 1 ETH + 2000 USDC
+    contract
+        .update_user_position(U256::from(1000) * scale, scale, U256::from(2000) * scale)
+        .send()
+        .await?
+        .await?;
+
+    // Sync pool state and prices: 500 ETH + 1M USDC, ETH = $2000
+    contract
+        .update_pool_state(U256::from(500) * scale, U256::from(1_000_000) * scale, U256::from(1_000_000) * scale)
+        .send()
+        .await?
+        .await?;
+    contract
+        .update_prices(U256::from(2000) * scale, scale)
+        .send()
+        .await?
+        .await?;
+
+    assert_eq!(contract.calculate_il().call().await?, scale / 2);
+    assert_eq!(contract.calculate_payout().call().await?, U256::from(320) * scale);
+
+    // Claim into a daily epoch and check the queued record
+    contract.set_epoch_duration(U256::from(86_400)).send().await?.await?;
+    contract.claim().send().await?.await?;
+    assert_eq!(contract.get_claim_count().call().await?, U256::from(1));
+    let (claimant, _, amount, _, _, _, _) = contract.get_claim(U256::zero()).call().await?;
+    assert_eq!(claimant, wallet.address());
+    assert_eq!(amount, U256::from(320) * scale);
+
+    Ok(())
+}
+
+/// Deploy the contract with `cargo stylus deploy` and return its address
+fn deploy(rpc_url: &str, priv_key_path: &str) -> eyre::Result<Address> {
+    let output = Command::new("cargo")
+        .args(["stylus", "deploy", "--no-verify"])
+        .arg(format!("--endpoint={}", rpc_url))
+        .arg(format!("--private-key-path={}", priv_key_path))
+        .output()?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "cargo stylus deploy failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let stdout = strip_ansi(&String::from_utf8_lossy(&output.stdout));
+    stdout
+        .lines()
+        .filter(|line| line.contains("deployed code at address"))
+        .find_map(|line| line.split_whitespace().last())
+        .ok_or_else(|| eyre!("No deployment address in output:\n{}", stdout))?
+        .parse()
+        .map_err(Into::into)
+}
+
+/// Remove terminal color codes from cargo-stylus output
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip until the end of the escape sequence
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn read_secret_from_file(fpath: &str) -> eyre::Result<String> {
+    let f = std::fs::File::open(fpath)?;
+    let mut buf_reader = BufReader::new(f);
+    let mut secret = String::new();
+    buf_reader.read_line(&mut secret)?;
+    Ok(secret.trim().to_string())
+}
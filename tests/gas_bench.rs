@@ -0,0 +1,140 @@
+//! Gas regression benchmarks for the IL Insurance contract.
+//!
+//! Estimates gas for the claim path, the payout calculation and the owner-side
+//! registry updates against a deployed instance (e.g. a local nitro-devnode), and
+//! fails if any entrypoint exceeds its budget. Uses the same env vars as the
+//! `counter` example. Run with:
+//!
+//! ```bash
+//! cargo test --features bench --test gas_bench -- --nocapture
+//! ```
+
+#![cfg(feature = "bench")]
+
+use dotenv::dotenv;
+use ethers::{
+    middleware::SignerMiddleware,
+    prelude::abigen,
+    providers::{Http, Middleware, Provider},
+    signers::{LocalWallet, Signer},
+    types::{Address, U256},
+};
+use eyre::eyre;
+use std::io::{BufRead, BufReader};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Your private key file path.
+const PRIV_KEY_PATH: &str = "PRIV_KEY_PATH";
+
+/// Stylus RPC endpoint url.
+const RPC_URL: &str = "RPC_URL";
+
+/// Deployed program address.
+const STYLUS_CONTRACT_ADDRESS: &str = "STYLUS_CONTRACT_ADDRESS";
+
+// Gas budgets per entrypoint. Tighten these as optimizations land; a failure
+// here means a change made the entrypoint more expensive than before.
+const BUDGET_CLAIM: u64 = 300_000;
+const BUDGET_CALCULATE_PAYOUT: u64 = 120_000;
+const BUDGET_UPDATE_POOL_STATE: u64 = 150_000;
+const BUDGET_UPDATE_PRICES: u64 = 120_000;
+const BUDGET_UPDATE_USER_POSITION: u64 = 150_000;
+
+abigen!(
+    ILInsurance,
+    r#"[
+        function initialize(uint256 threshold_bps, uint256 upper_cap_bps, uint256 payout_ratio_bps) external
+        function isInitialized() external view returns (bool)
+        function setupDemo() external
+        function calculatePayout() external view returns (uint256)
+        function claim() external returns (uint256)
+        function updatePoolState(uint256 reserve_a, uint256 reserve_b, uint256 total_supply) external
+        function updatePrices(uint256 price_a, uint256 price_b) external
+        function updateUserPosition(uint256 lp_amount, uint256 original_a, uint256 original_b) external
+    ]"#
+);
+
+#[tokio::test]
+async fn test_gas_within_budget() -> eyre::Result<()> {
+    dotenv().ok();
+    let priv_key_path =
+        std::env::var(PRIV_KEY_PATH).map_err(|_| eyre!("No {} env var set", PRIV_KEY_PATH))?;
+    let rpc_url = std::env::var(RPC_URL).map_err(|_| eyre!("No {} env var set", RPC_URL))?;
+    let contract_address = std::env::var(STYLUS_CONTRACT_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", STYLUS_CONTRACT_ADDRESS))?;
+
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let address: Address = contract_address.parse()?;
+
+    let privkey = read_secret_from_file(&priv_key_path)?;
+    let wallet = LocalWallet::from_str(&privkey)?;
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let client = Arc::new(SignerMiddleware::new(
+        provider,
+        wallet.clone().with_chain_id(chain_id),
+    ));
+
+    let contract = ILInsurance::new(address, client);
+    if !contract.is_initialized().call().await? {
+        contract
+            .initialize(U256::from(1000), U256::from(2000), U256::from(8000))
+            .send()
+            .await?
+            .await?;
+    }
+    contract.setup_demo().send().await?.await?;
+
+    let scale = U256::exp10(18);
+    let measurements = [
+        ("claim", contract.claim().estimate_gas().await?, BUDGET_CLAIM),
+        (
+            "calculatePayout",
+            contract.calculate_payout().estimate_gas().await?,
+            BUDGET_CALCULATE_PAYOUT,
+        ),
+        (
+            "updatePoolState",
+            contract
+                .update_pool_state(U256::from(500) * scale, U256::from(1_000_000) * scale, U256::from(1_000_000) * scale)
+                .estimate_gas()
+                .await?,
+            BUDGET_UPDATE_POOL_STATE,
+        ),
+        (
+            "updatePrices",
+            contract
+                .update_prices(U256::from(2000) * scale, scale)
+                .estimate_gas()
+                .await?,
+            BUDGET_UPDATE_PRICES,
+        ),
+        (
+            "updateUserPosition",
+            contract
+                .update_user_position(U256::from(1000) * scale, scale, U256::from(2000) * scale)
+                .estimate_gas()
+                .await?,
+            BUDGET_UPDATE_USER_POSITION,
+        ),
+    ];
+
+    let mut over_budget = Vec::new();
+    for (name, gas, budget) in measurements {
+        println!("{:<20} {:>10} gas (budget {})", name, gas, budget);
+        if gas > U256::from(budget) {
+            over_budget.push(name);
+        }
+    }
+
+    assert!(over_budget.is_empty(), "Over gas budget: {:?}", over_budget);
+    Ok(())
+}
+
+fn read_secret_from_file(fpath: &str) -> eyre::Result<String> {
+    let f = std::fs::File::open(fpath)?;
+    let mut buf_reader = BufReader::new(f);
+    let mut secret = String::new();
+    buf_reader.read_line(&mut secret)?;
+    Ok(secret.trim().to_string())
+}
@@ -0,0 +1,50 @@
+//! The crate as an off-chain dependency: pure types and math on a std target.
+//!
+//! Builds the library with the `types` feature (no contract entrypoint) and uses it the
+//! way analysis tooling would. Run with:
+//!
+//! ```bash
+//! cargo test --features types --test types
+//! ```
+
+#![cfg(feature = "types")]
+
+use alloy_primitives::U256;
+use stylus_hello_world::{
+    compute_values, params, pricing, ILInsuranceError, ParamError, PolicyParams,
+    CONSTANT_SCALE as SCALE,
+};
+
+#[test]
+fn test_pure_math_off_chain() {
+    // The demo position: 1000 of 1M LP over 500 ETH + 1M USDC at $2000, entered at 1 ETH + 2000 USDC
+    let (lp_value, holding_value, il_frac) = compute_values(
+        (500 * SCALE, 1_000_000 * SCALE),
+        1_000_000 * SCALE,
+        1000 * SCALE,
+        (2000 * SCALE, SCALE),
+        (SCALE, 2000 * SCALE),
+    );
+    assert_eq!((lp_value, holding_value, il_frac), (2000 * SCALE, 4000 * SCALE, SCALE / 2));
+
+    let policy = PolicyParams::new(U256::from(1000u32), U256::from(2000u32), U256::from(8000u32));
+    assert_eq!(params::validate_policy(&policy), Ok(()));
+    let premium = pricing::quote_premium(
+        U256::from(4000 * SCALE),
+        U256::from(SCALE),
+        365 * 24 * 60 * 60,
+        policy.threshold_bps,
+        policy.upper_cap_bps,
+        policy.payout_ratio_bps,
+    );
+    assert!(premium > U256::ZERO && premium < U256::from(320 * SCALE));
+}
+
+#[test]
+fn test_types_off_chain() {
+    // Parameter errors map onto the contract's custom errors
+    let inverted = PolicyParams::new(U256::from(2000u32), U256::from(1000u32), U256::ZERO);
+    let error = params::validate_policy(&inverted).unwrap_err();
+    assert_eq!(error, ParamError::InvalidThreshold);
+    assert!(matches!(ILInsuranceError::from(error), ILInsuranceError::InvalidThreshold(_)));
+}
@@ -0,0 +1,75 @@
+//! Wasm size budget for the production build.
+//!
+//! Runs `cargo stylus check` on the default feature set (no demo fixtures) and
+//! fails if the compressed contract is over the Stylus 24 KB limit. Needs
+//! `cargo-stylus` and an RPC endpoint for the activation check. Run with:
+//!
+//! ```bash
+//! cargo test --features bench --test wasm_size -- --nocapture
+//! ```
+
+#![cfg(feature = "bench")]
+
+use std::process::Command;
+
+/// Stylus rejects programs whose compressed wasm exceeds 24 KB
+const MAX_COMPRESSED_SIZE_BYTES: f64 = 24.0 * 1024.0;
+
+/// Stylus RPC endpoint url (optional, cargo-stylus defaults to Arbitrum Sepolia).
+const RPC_URL: &str = "RPC_URL";
+
+#[test]
+fn test_compressed_wasm_within_budget() {
+    let mut command = Command::new("cargo");
+    command.args(["stylus", "check"]);
+    if let Ok(rpc_url) = std::env::var(RPC_URL) {
+        command.arg(format!("--endpoint={}", rpc_url));
+    }
+
+    let output = command.output().expect("failed to run cargo stylus check");
+    let stdout = strip_ansi(&String::from_utf8_lossy(&output.stdout));
+    assert!(
+        output.status.success(),
+        "cargo stylus check failed:\n{}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let size = compressed_size_bytes(&stdout)
+        .unwrap_or_else(|| panic!("No contract size in cargo stylus output:\n{}", stdout));
+    println!("Compressed wasm size: {:.1} KB (budget {:.1} KB)", size / 1024.0, MAX_COMPRESSED_SIZE_BYTES / 1024.0);
+    assert!(size <= MAX_COMPRESSED_SIZE_BYTES, "Compressed wasm is {} bytes", size);
+}
+
+/// Parse the size reported on the "... size: 8.9 KB" line of `cargo stylus check`
+fn compressed_size_bytes(output: &str) -> Option<f64> {
+    let line = output.lines().find(|line| line.to_lowercase().contains("size:"))?;
+    let value = line.split(':').nth(1)?.trim();
+    let mut parts = value.split_whitespace();
+    let number: f64 = parts.next()?.parse().ok()?;
+    match parts.next()?.to_uppercase().as_str() {
+        "B" => Some(number),
+        "KB" | "KIB" => Some(number * 1024.0),
+        "MB" | "MIB" => Some(number * 1024.0 * 1024.0),
+        _ => None,
+    }
+}
+
+/// Remove terminal color codes from cargo-stylus output
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip until the end of the escape sequence
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}